@@ -18,4 +18,4 @@ mod tests {
     }
 }
 
-aoc::solve!($YEAR, $DAY, p1, p2);
\ No newline at end of file
+crate::solve!($YEAR, $DAY, $TITLE, p1, p2);
\ No newline at end of file
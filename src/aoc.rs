@@ -1,35 +1,121 @@
-use std::io;
-use std::path::PathBuf;
-
-#[derive(Debug)]
-pub struct Error(String);
-impl From<String> for Error {
-    fn from(e: String) -> Self {
-        Self(e)
-    }
+pub use error::{Error, ParseError};
+pub use solution::{Answer, Day, IntoAnswer, Run, Solution};
+
+pub mod bench;
+pub mod check;
+mod error;
+pub mod fetch;
+pub mod graph;
+pub mod grid;
+pub mod image;
+pub mod math;
+pub mod parse;
+pub mod ranges;
+pub mod scaffold;
+mod solution;
+
+pub mod y2015;
+pub mod y2022;
+pub mod y2023;
+
+/// Every registered puzzle, grouped by year in chronological order.
+pub const YEARS: &[&[Day]] = &[y2015::DAYS, y2022::DAYS, y2023::DAYS];
+
+/// Reads the input of day `d` of year `y`, downloading it on first use.
+pub fn pull_input(y: usize, d: usize) -> Result<String, Error> {
+    fetch::Fetcher::from_env().input(y, d)
 }
 
-impl From<io::Error> for Error {
-    fn from(e: io::Error) -> Self {
-        Self(e.to_string())
-    }
+pub fn days() -> impl Iterator<Item = &'static Day> {
+    YEARS.iter().flat_map(|days| days.iter())
 }
 
-pub fn pull_input(y: usize, d: usize) -> Result<String, std::io::Error> {
-    let path = PathBuf::from(format!("inputs/{y}/{:02?}.txt", d));
-    std::fs::read_to_string(path)
+pub fn days_of(year: usize) -> Result<&'static [Day], Error> {
+    YEARS
+        .iter()
+        .find(|days| days.first().is_some_and(|d| d.year == year))
+        .copied()
+        .ok_or_else(|| format!("no days registered for {year}").into())
 }
 
+pub fn find_day(year: usize, day: usize) -> Result<&'static Day, Error> {
+    days_of(year)?
+        .iter()
+        .find(|d| d.day == day)
+        .ok_or_else(|| format!("{year} day {day} is not registered").into())
+}
+
+/// Registers a day as `pub const DAY`.
+///
+/// `p1` and `p2` take the raw input, or the output of `parse` when one is
+/// given, in which case the day module must name that output `Input<'a>`.
+/// A trailing `render = f` pictures the same input for `--render`, or fails
+/// with an error when it can't.
 #[macro_export]
 macro_rules! solve {
-    ($year:expr, $day:expr, $p1:ident, $p2:ident) => {
-        pub fn solve() -> Result<(), aoc::Error> {
-            let input = aoc::pull_input($year, $day)?;
+    ($year:expr, $day:expr, $title:expr, $p1:ident, $p2:ident $(, render = $render:ident)?) => {
+        $crate::solve!(@impl $year, $day, $title, &'a str, Ok, $p1, $p2 $(, $render)?);
+    };
+    ($year:expr, $day:expr, $title:expr, $parse:ident, $p1:ident, $p2:ident $(, render = $render:ident)?) => {
+        $crate::solve!(@impl $year, $day, $title, Input<'a>, $parse, $p1, $p2 $(, $render)?);
+    };
+    (@impl $year:expr, $day:expr, $title:expr, $input:ty, $parse:path, $p1:ident, $p2:ident $(, $render:ident)?) => {
+        pub struct Puzzle;
+
+        impl $crate::Solution for Puzzle {
+            const YEAR: usize = $year;
+            const DAY: usize = $day;
+            const TITLE: &'static str = $title;
 
-            println!("{}", $p1(&input));
-            println!("{}", $p2(&input));
+            type Input<'a> = $input;
 
-            Ok(())
+            fn parse(input: &str) -> Result<Self::Input<'_>, $crate::Error> {
+                $parse(input)
+            }
+
+            fn part1(input: &Self::Input<'_>) -> Result<$crate::Answer, $crate::Error> {
+                $crate::IntoAnswer::into_answer($p1(input))
+            }
+
+            fn part2(input: &Self::Input<'_>) -> Result<$crate::Answer, $crate::Error> {
+                $crate::IntoAnswer::into_answer($p2(input))
+            }
+
+            $(
+                fn render(
+                    input: &Self::Input<'_>,
+                ) -> Result<Option<$crate::image::Image>, $crate::Error> {
+                    $render(input).map(Some)
+                }
+            )?
         }
+
+        pub const DAY: $crate::Day = $crate::Day::of::<Puzzle>();
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        for days in YEARS {
+            let year = days[0].year;
+            assert!(days.iter().all(|d| d.year == year), "{year} mixes years");
+            assert!(
+                days.windows(2).all(|w| w[0].day < w[1].day),
+                "{year} days are not sorted"
+            );
+        }
+        assert!(YEARS.windows(2).all(|w| w[0][0].year < w[1][0].year));
+    }
+
+    #[test]
+    fn test_find_day() {
+        assert_eq!(find_day(2015, 7).unwrap().day, 7);
+        assert_eq!(find_day(2023, 8).unwrap().year, 2023);
+        assert!(find_day(2023, 26).is_err());
+        assert!(days_of(2014).is_err());
+    }
+}
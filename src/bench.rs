@@ -0,0 +1,167 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{pull_input, Day, Error};
+
+/// Where `bench` appends its results, one CSV row per timed stage.
+pub const HISTORY_PATH: &str = "bench_history.csv";
+
+const HISTORY_HEADER: &str = "timestamp,commit,year,day,stage,runs,min_ns,median_ns,mean_ns";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    pub fn of(samples: &mut [Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort();
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / samples.len() as u32,
+        }
+    }
+}
+
+/// Timings of one day over repeated runs.
+pub struct Bench {
+    pub year: usize,
+    pub day: usize,
+    pub runs: usize,
+    pub stages: [(&'static str, Stats); 3],
+}
+
+/// Times parsing, part 1 and part 2 of `day` separately, `runs` times each.
+pub fn bench(day: &Day, runs: usize) -> Result<Bench, Error> {
+    if runs == 0 {
+        return Err("benchmarks need at least one run".to_string().into());
+    }
+
+    let input = pull_input(day.year, day.day)?;
+    let mut samples = [(); 3].map(|_| Vec::with_capacity(runs));
+
+    for _ in 0..runs {
+        let run = (day.run)(&input, None)?;
+        samples[0].push(run.parse);
+        for (part, samples) in run.parts.into_iter().zip(&mut samples[1..]) {
+            let (answer, elapsed) = part.expect("both parts were run");
+            answer?;
+            samples.push(elapsed);
+        }
+    }
+
+    let [parse, part1, part2] = samples.map(|mut s| Stats::of(&mut s));
+
+    Ok(Bench {
+        year: day.year,
+        day: day.day,
+        runs,
+        stages: [("parse", parse), ("part1", part1), ("part2", part2)],
+    })
+}
+
+/// Appends `results` to the CSV history at `path`, tagged with the current
+/// time and git commit so runs can be compared across commits.
+pub fn append_history(path: impl AsRef<Path>, results: &[Bench]) -> Result<(), Error> {
+    let path = path.as_ref();
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    if is_new {
+        writeln!(file, "{HISTORY_HEADER}")?;
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let commit = current_commit().unwrap_or_else(|| "unknown".into());
+
+    for bench in results {
+        for row in history_rows(bench) {
+            writeln!(file, "{timestamp},{commit},{row}")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn history_rows(bench: &Bench) -> impl Iterator<Item = String> + '_ {
+    bench.stages.iter().map(|(stage, stats)| {
+        format!(
+            "{},{},{stage},{},{},{},{}",
+            bench.year,
+            bench.day,
+            bench.runs,
+            stats.min.as_nanos(),
+            stats.median.as_nanos(),
+            stats.mean.as_nanos()
+        )
+    })
+}
+
+fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(
+            Stats::of(&mut [ms(5), ms(1), ms(3)]),
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3)
+            }
+        );
+        assert_eq!(Stats::of(&mut [ms(4), ms(1), ms(2), ms(9)]).median, ms(3));
+    }
+
+    #[test]
+    fn test_history_rows() {
+        let stats = Stats::of(&mut [ms(2)]);
+        let bench = Bench {
+            year: 2015,
+            day: 4,
+            runs: 1,
+            stages: [("parse", stats), ("part1", stats), ("part2", stats)],
+        };
+
+        assert_eq!(
+            history_rows(&bench).next().unwrap(),
+            "2015,4,parse,1,2000000,2000000,2000000"
+        );
+    }
+}
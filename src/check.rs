@@ -0,0 +1,149 @@
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+use toml::{Table, Value};
+
+use crate::{pull_input, Answer, Day, Error};
+
+/// Known answers of a year, keyed by day. A part is `None` until its answer
+/// has been recorded.
+pub type Answers = BTreeMap<usize, [Option<Answer>; 2]>;
+
+/// Where the known answers of `year` live, next to its inputs.
+///
+/// ```toml
+/// [7]
+/// part1 = 46065
+/// part2 = 14134
+///
+/// [11]
+/// part1 = "hepxxyzz"
+/// ```
+pub fn answers_path(year: usize) -> PathBuf {
+    PathBuf::from(format!("inputs/{year}/answers.toml"))
+}
+
+/// Loads the known answers of `year`; a missing file means nothing is known.
+pub fn load_answers(year: usize) -> Result<Answers, Error> {
+    let path = answers_path(year);
+    if !path.exists() {
+        return Ok(Answers::new());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    parse_answers(&content).map_err(|e| format!("{}: {e}", path.display()).into())
+}
+
+fn parse_answers(content: &str) -> Result<Answers, Error> {
+    let table: Table = content.parse().map_err(|e| format!("{e}"))?;
+    let mut answers = Answers::new();
+
+    for (key, parts) in table {
+        let day = key.parse().map_err(|_| format!("invalid day {key:?}"))?;
+        let parts = parts
+            .as_table()
+            .ok_or_else(|| format!("day {day} is not a table"))?;
+
+        let mut entry = [None, None];
+        for (part, slot) in ["part1", "part2"].iter().zip(entry.iter_mut()) {
+            *slot = parts.get(*part).map(to_answer).transpose()?;
+        }
+        answers.insert(day, entry);
+    }
+
+    Ok(answers)
+}
+
+fn to_answer(value: &Value) -> Result<Answer, Error> {
+    match value {
+        Value::Integer(n) => Ok(Answer::Num(*n as i128)),
+        // integers that don't fit TOML's i64 are written as strings
+        Value::String(s) => Ok(s.parse().unwrap_or_else(|e| match e {})),
+        v => Err(format!("unsupported answer {v}").into()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail { expected: Answer },
+    Missing,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => f.write_str("pass"),
+            Self::Fail { expected } => write!(f, "FAIL, expected {expected}"),
+            Self::Missing => f.write_str("missing"),
+        }
+    }
+}
+
+impl Status {
+    pub fn of(answer: &Answer, expected: Option<&Answer>) -> Self {
+        match expected {
+            None => Self::Missing,
+            Some(expected) if expected == answer => Self::Pass,
+            Some(expected) => Self::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+/// Outcome of checking one part: its answer and how it compares, or why it
+/// couldn't be solved.
+pub type Checked = Result<(Answer, Status), Error>;
+
+/// Runs both parts of `day` against its input and compares them with the
+/// `expected` answers. A failing part doesn't prevent checking the other one.
+pub fn check(day: &Day, expected: &[Option<Answer>; 2]) -> Result<[Checked; 2], Error> {
+    let input = pull_input(day.year, day.day)?;
+    let mut parts = (day.run)(&input, None)?.parts.into_iter();
+
+    Ok(std::array::from_fn(|i| {
+        let (answer, _) = parts.next().flatten().expect("both parts were run");
+        let answer = answer?;
+        let status = Status::of(&answer, expected[i].as_ref());
+        Ok((answer, status))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers(
+            "[1]\n\
+            part1 = 280\n\
+            part2 = 1797\n\
+            \n\
+            [11]\n\
+            part1 = \"hepxxyzz\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            answers[&1],
+            [Some(Answer::Num(280)), Some(Answer::Num(1797))]
+        );
+        assert_eq!(answers[&11], [Some("hepxxyzz".into()), None]);
+        assert!(parse_answers("[x]\npart1 = 1").is_err());
+        assert!(parse_answers("[1]\npart1 = 1.5").is_err());
+    }
+
+    #[test]
+    fn test_status() {
+        let answer = Answer::Num(42);
+        assert_eq!(Status::of(&answer, Some(&42usize.into())), Status::Pass);
+        assert_eq!(Status::of(&answer, None), Status::Missing);
+        assert_eq!(
+            Status::of(&answer, Some(&41usize.into())),
+            Status::Fail {
+                expected: 41usize.into()
+            }
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::{error, fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse(ParseError),
+    /// The input parsed fine but admits no answer, e.g. a search that can't
+    /// terminate or a state the puzzle says can't happen.
+    Unsolvable(String),
+    Other(String),
+}
+
+impl Error {
+    pub fn unsolvable(reason: impl Into<String>) -> Self {
+        Self::Unsolvable(reason.into())
+    }
+
+    /// Tags parse errors with the puzzle they come from.
+    pub fn in_day(self, year: usize, day: usize) -> Self {
+        match self {
+            Self::Parse(e) => Self::Parse(ParseError {
+                puzzle: Some((year, day)),
+                ..e
+            }),
+            e => e,
+        }
+    }
+}
+
+impl From<String> for Error {
+    fn from(e: String) -> Self {
+        Self::Other(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::Unsolvable(reason) => write!(f, "unsolvable: {reason}"),
+            Self::Other(e) => f.write_str(e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Where and why an input could not be parsed. `line` and `column` are
+/// 1-based, `column` counts characters of `text`, the offending line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub puzzle: Option<(usize, usize)>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, reason: impl Into<String>) -> Self {
+        Self {
+            puzzle: None,
+            line,
+            column,
+            text: text.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Points at `rest`, a subslice of `text` (usually what a parser could not
+    /// consume), on the 0-based line `index`.
+    pub fn at(index: usize, text: &str, rest: &str, reason: impl Into<String>) -> Self {
+        let offset = (rest.as_ptr() as usize)
+            .checked_sub(text.as_ptr() as usize)
+            .filter(|&offset| offset <= text.len())
+            .unwrap_or(text.len().saturating_sub(rest.len()));
+        let column = text
+            .get(..offset)
+            .map_or(offset, |prefix| prefix.chars().count())
+            + 1;
+
+        Self::new(index + 1, column, text, reason)
+    }
+
+    /// Converts the error of a nom parser run on the 0-based line `index`.
+    pub fn nom(index: usize, text: &str, e: nom::Err<nom::error::Error<&str>>) -> Self {
+        match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => Self::at(
+                index,
+                text,
+                e.input,
+                format!("expected {}", e.code.description()),
+            ),
+            nom::Err::Incomplete(_) => Self::at(index, text, "", "unexpected end of line"),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((year, day)) = self.puzzle {
+            write!(f, "{year} day {day:02}, ")?;
+        }
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.reason
+        )?;
+        writeln!(f, "    {}", self.text)?;
+        write!(f, "    {:>1$}", "^", self.column)
+    }
+}
+
+impl error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_at() {
+        let text = "x ANDD y -> d";
+        let e = ParseError::at(2, text, &text[2..], "expected operator");
+
+        assert_eq!((e.line, e.column), (3, 3));
+        assert_eq!(
+            Error::from(e).in_day(2015, 7).to_string(),
+            "2015 day 07, line 3, column 3: expected operator\n\
+            \x20   x ANDD y -> d\n\
+            \x20     ^"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_nom() {
+        let text = "1 2 x";
+        let e = nom::sequence::tuple((
+            nom::character::complete::u8::<_, nom::error::Error<_>>,
+            nom::character::complete::char(' '),
+            nom::character::complete::u8,
+        ))(&text[2..])
+        .unwrap_err();
+
+        let e = ParseError::nom(0, text, e);
+        assert_eq!((e.line, e.column), (1, 5));
+        assert_eq!(e.reason, "expected Digit");
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::Error;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = "github.com/nadirs/aoc";
+
+/// Downloads puzzle inputs and caches them on disk, so each input is only
+/// ever requested once.
+pub struct Fetcher {
+    base_url: String,
+    session: Option<String>,
+    cache_dir: PathBuf,
+}
+
+impl Fetcher {
+    pub fn new(
+        base_url: impl Into<String>,
+        session: Option<String>,
+        cache_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            base_url: base_url.into(),
+            session,
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    /// Reads the session cookie from `AOC_SESSION` and the server from
+    /// `AOC_BASE_URL`, caching under `inputs/`.
+    pub fn from_env() -> Self {
+        Self::new(
+            env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into()),
+            env::var("AOC_SESSION").ok().filter(|s| !s.is_empty()),
+            "inputs",
+        )
+    }
+
+    pub fn path(&self, year: usize, day: usize) -> PathBuf {
+        self.cache_dir.join(format!("{year}/{day:02}.txt"))
+    }
+
+    /// Returns the cached input, downloading it first if it isn't there yet.
+    pub fn input(&self, year: usize, day: usize) -> Result<String, Error> {
+        let path = self.path(year, day);
+        if path.exists() {
+            return Ok(fs::read_to_string(path)?);
+        }
+
+        let input = self.download(year, day, &path)?;
+        store(&path, &input)?;
+
+        Ok(input)
+    }
+
+    fn download(&self, year: usize, day: usize, path: &Path) -> Result<String, Error> {
+        let session = self.session.as_deref().ok_or_else(|| {
+            format!(
+                "{} is missing and AOC_SESSION is not set to download it",
+                path.display()
+            )
+        })?;
+        let url = format!(
+            "{}/{year}/day/{day}/input",
+            self.base_url.trim_end_matches('/')
+        );
+
+        let response = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(USER_AGENT)
+            .build()
+            .get(&url)
+            .set("Cookie", &format!("session={session}"))
+            .call()
+            .map_err(|e| match e {
+                ureq::Error::Status(code, _) => format!("GET {url}: HTTP {code}"),
+                e => format!("GET {url}: {e}"),
+            })?;
+
+        Ok(response
+            .into_string()
+            .map_err(|e| format!("GET {url}: {e}"))?)
+    }
+}
+
+/// Writes through a temporary file so an interrupted download never leaves a
+/// truncated input in the cache.
+fn store(path: &Path, input: &str) -> Result<(), Error> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp = path.with_extension("txt.part");
+    fs::write(&tmp, input)?;
+    fs::rename(tmp, path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        process,
+        sync::mpsc::{self, Receiver},
+        thread,
+    };
+
+    use super::*;
+
+    /// Serves `responses` in order, reporting each request line and cookie.
+    fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, Receiver<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut cookie = String::new();
+                for line in reader.lines() {
+                    let line = line.unwrap();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(c) = line.strip_prefix("Cookie: ") {
+                        cookie = c.into();
+                    }
+                }
+                tx.send((request_line.trim().to_string(), cookie)).unwrap();
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        (url, rx)
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let (url, requests) = stub_server(vec![(200, "1 2 3\n")]);
+        let dir = cache_dir("cache");
+        let fetcher = Fetcher::new(url, Some("s3cr3t".into()), &dir);
+
+        assert_eq!(fetcher.input(2015, 4).unwrap(), "1 2 3\n");
+        assert_eq!(
+            requests.recv().unwrap(),
+            (
+                "GET /2015/day/4/input HTTP/1.1".into(),
+                "session=s3cr3t".into()
+            )
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("2015/04.txt")).unwrap(),
+            "1 2 3\n"
+        );
+
+        // served from the cache: the stub would refuse a second connection
+        assert_eq!(fetcher.input(2015, 4).unwrap(), "1 2 3\n");
+        assert!(requests.try_recv().is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let (url, _requests) = stub_server(vec![(404, "Not Found")]);
+        let dir = cache_dir("errors");
+
+        let anonymous = Fetcher::new(url.clone(), None, &dir);
+        assert!(anonymous.input(2023, 25).is_err());
+
+        let fetcher = Fetcher::new(url, Some("s3cr3t".into()), &dir);
+        assert!(fetcher.input(2023, 25).is_err());
+        assert!(!fetcher.path(2023, 25).exists());
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BTreeMap, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// What an edge costs to follow.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = C>> Cost for C {}
+
+/// A directed graph, given by the edges leaving each node.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+    type Cost: Cost;
+
+    fn neighbours(&self, node: &Self::Node) -> impl Iterator<Item = (Self::Node, Self::Cost)>;
+}
+
+impl<N: Clone + Eq + Hash, C: Cost> Graph for HashMap<N, Vec<(N, C)>> {
+    type Node = N;
+    type Cost = C;
+
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = (N, C)> {
+        self.get(node).into_iter().flatten().cloned()
+    }
+}
+
+impl<N: Clone + Eq + Hash, C: Cost> Graph for HashMap<N, HashMap<N, C>> {
+    type Node = N;
+    type Cost = C;
+
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = (N, C)> {
+        self.get(node)
+            .into_iter()
+            .flatten()
+            .map(|(n, &c)| (n.clone(), c))
+    }
+}
+
+impl<N: Clone + Ord + Hash, C: Cost> Graph for BTreeMap<N, BTreeMap<N, C>> {
+    type Node = N;
+    type Cost = C;
+
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = (N, C)> {
+        self.get(node)
+            .into_iter()
+            .flatten()
+            .map(|(n, &c)| (n.clone(), c))
+    }
+}
+
+/// A path through a graph and its total cost.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+/// How many edges away from `start` each reachable node is.
+pub fn bfs<G: Graph>(graph: &G, start: G::Node) -> HashMap<G::Node, usize> {
+    let mut steps = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        let n = steps[&node];
+        for (next, _) in graph.neighbours(&node) {
+            if let Entry::Vacant(e) = steps.entry(next.clone()) {
+                e.insert(n + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    steps
+}
+
+/// The cheapest path from `start` to a node satisfying `is_goal`.
+pub fn dijkstra<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl FnMut(&G::Node) -> bool,
+) -> Option<Path<G::Node, G::Cost>> {
+    astar(graph, start, is_goal, |_| G::Cost::default())
+}
+
+/// The cheapest path from `start` to a node satisfying `is_goal`, exploring
+/// first the nodes whose cost plus `heuristic` is lowest. The heuristic must
+/// never overestimate the remaining cost for the path to be the cheapest.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+    mut heuristic: impl FnMut(&G::Node) -> G::Cost,
+) -> Option<Path<G::Node, G::Cost>> {
+    // nodes are numbered as they are discovered, so that the heap doesn't
+    // need them to be ordered
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![(start.clone(), 0, G::Cost::default())];
+    let mut done = vec![false];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0))]);
+
+    while let Some(Reverse((_, i))) = heap.pop() {
+        if std::mem::replace(&mut done[i], true) {
+            continue;
+        }
+
+        let (node, _, cost) = nodes[i].clone();
+        if is_goal(&node) {
+            let mut path = vec![node];
+            let mut j = i;
+            while j != 0 {
+                j = nodes[j].1;
+                path.push(nodes[j].0.clone());
+            }
+            path.reverse();
+            return Some(Path { cost, nodes: path });
+        }
+
+        for (next, step) in graph.neighbours(&node) {
+            let next_cost = cost + step;
+            let j = match index.entry(next.clone()) {
+                Entry::Occupied(e) if next_cost < nodes[*e.get()].2 => *e.get(),
+                Entry::Occupied(_) => continue,
+                Entry::Vacant(e) => {
+                    e.insert(nodes.len());
+                    nodes.push((next.clone(), i, next_cost));
+                    done.push(false);
+                    nodes.len() - 1
+                }
+            };
+
+            nodes[j].1 = i;
+            nodes[j].2 = next_cost;
+            done[j] = false;
+            heap.push(Reverse((next_cost + heuristic(&next), j)));
+        }
+    }
+
+    None
+}
+
+/// Orders the nodes reachable from `roots` so that every edge goes from a
+/// node to a later one. Fails with a node on a cycle if there is one.
+pub fn topo_sort<G: Graph>(
+    graph: &G,
+    roots: impl IntoIterator<Item = G::Node>,
+) -> Result<Vec<G::Node>, G::Node> {
+    // false while a node is on the stack, true once all its successors are
+    let mut finished = HashMap::new();
+    let mut order = vec![];
+
+    for root in roots {
+        if finished.contains_key(&root) {
+            continue;
+        }
+        finished.insert(root.clone(), false);
+        let mut stack = vec![(root.clone(), successors(graph, &root))];
+
+        while let Some((_, next)) = stack.last_mut() {
+            match next.next() {
+                Some(n) => match finished.get(&n) {
+                    Some(false) => return Err(n),
+                    Some(true) => {}
+                    None => {
+                        finished.insert(n.clone(), false);
+                        let next = successors(graph, &n);
+                        stack.push((n, next));
+                    }
+                },
+                None => {
+                    let (node, _) = stack.pop().expect("the stack is not empty");
+                    finished.insert(node.clone(), true);
+                    order.push(node);
+                }
+            }
+        }
+    }
+
+    order.reverse();
+    Ok(order)
+}
+
+fn successors<G: Graph>(graph: &G, node: &G::Node) -> std::vec::IntoIter<G::Node> {
+    graph
+        .neighbours(node)
+        .map(|(n, _)| n)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Whether the best tour is the cheapest or the most expensive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    Min,
+    Max,
+}
+
+impl Objective {
+    fn better<C: Ord>(self, a: C, b: C) -> bool {
+        match self {
+            Self::Min => a < b,
+            Self::Max => a > b,
+        }
+    }
+}
+
+/// Whether a tour may end anywhere or must get back to where it started.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tour {
+    Open,
+    Closed,
+}
+
+/// The best tour through all `n` nodes, visiting each once, using the
+/// Held-Karp dynamic programming algorithm in O(2^n n^2) time.
+///
+/// `cost(a, b)` is the cost of going from node `a` to node `b`, or `None` if
+/// there is no such edge. Closed tours start and end at node 0, whose cost
+/// to get back to is included.
+pub fn held_karp<C: Cost>(
+    n: usize,
+    cost: impl Fn(usize, usize) -> Option<C>,
+    objective: Objective,
+    tour: Tour,
+) -> Option<Path<usize, C>> {
+    assert!(n < 32, "too many nodes for Held-Karp: {n}");
+    if n <= 1 {
+        return (n == 1).then(|| Path {
+            cost: C::default(),
+            nodes: vec![0],
+        });
+    }
+
+    let all = (1 << n) - 1;
+    // best[visited][last] is the best cost of a path through the `visited`
+    // set of nodes that ends at `last`, and the node before `last`
+    let mut best: Vec<Vec<Option<(C, usize)>>> = vec![vec![None; n]; 1 << n];
+    let starts = match tour {
+        Tour::Open => 0..n,
+        Tour::Closed => 0..1,
+    };
+    for start in starts {
+        best[1 << start][start] = Some((C::default(), start));
+    }
+
+    for visited in 1..all {
+        for last in 0..n {
+            let Some((c, _)) = best[visited][last] else {
+                continue;
+            };
+            for next in (0..n).filter(|next| visited & (1 << next) == 0) {
+                let Some(step) = cost(last, next) else {
+                    continue;
+                };
+                let c = c + step;
+                let slot = &mut best[visited | (1 << next)][next];
+                if slot.is_none_or(|(old, _)| objective.better(c, old)) {
+                    *slot = Some((c, last));
+                }
+            }
+        }
+    }
+
+    let (mut last, total) = (0..n)
+        .filter_map(|last| {
+            let (c, _) = best[all][last]?;
+            match tour {
+                Tour::Open => Some((last, c)),
+                Tour::Closed => Some((last, c + cost(last, 0)?)),
+            }
+        })
+        .reduce(|a, b| if objective.better(b.1, a.1) { b } else { a })?;
+
+    let mut nodes = vec![last];
+    let mut visited = all;
+    while visited != 1 << last {
+        let (_, prev) = best[visited][last].expect("every step of the tour is known");
+        visited &= !(1 << last);
+        last = prev;
+        nodes.push(last);
+    }
+    nodes.reverse();
+
+    Some(Path { cost: total, nodes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a -1-> b -1-> c -1-> d, a -5-> d, d -1-> e, plus a dead end at f.
+    fn graph() -> HashMap<char, Vec<(char, u32)>> {
+        HashMap::from([
+            ('a', vec![('b', 1), ('d', 5), ('f', 1)]),
+            ('b', vec![('c', 1)]),
+            ('c', vec![('d', 1)]),
+            ('d', vec![('e', 1)]),
+        ])
+    }
+
+    #[test]
+    fn test_bfs() {
+        let steps = bfs(&graph(), 'a');
+        assert_eq!(steps[&'d'], 1);
+        assert_eq!(steps[&'e'], 2);
+        assert_eq!(steps.len(), 6);
+        assert_eq!(bfs(&graph(), 'e').len(), 1);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let path = dijkstra(&graph(), 'a', |&n| n == 'e').unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes, ['a', 'b', 'c', 'd', 'e']);
+        assert_eq!(dijkstra(&graph(), 'b', |&n| n == 'a'), None);
+
+        let start = dijkstra(&graph(), 'a', |&n| n == 'a').unwrap();
+        assert_eq!((start.cost, start.nodes), (0, vec!['a']));
+    }
+
+    #[test]
+    fn test_astar() {
+        // walking a number line, moving by 1 or 3 at a cost of 1 each
+        struct Line;
+        impl Graph for Line {
+            type Node = i32;
+            type Cost = u32;
+
+            fn neighbours(&self, &n: &i32) -> impl Iterator<Item = (i32, u32)> {
+                [n - 1, n + 1, n + 3].into_iter().map(|n| (n, 1))
+            }
+        }
+
+        let path = astar(&Line, 0, |&n| n == 10, |&n| (10 - n).unsigned_abs() / 3).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes.last(), Some(&10));
+        assert_eq!(dijkstra(&Line, 0, |&n| n == 10).unwrap().cost, 4);
+    }
+
+    #[test]
+    fn test_topo_sort() {
+        let order = topo_sort(&graph(), ['c', 'a']).unwrap();
+        let pos = |n| order.iter().position(|&m| m == n).unwrap();
+        assert_eq!(order.len(), 6);
+        assert!(pos('a') < pos('b') && pos('b') < pos('c') && pos('c') < pos('d'));
+        assert!(pos('d') < pos('e'));
+
+        let mut cyclic = graph();
+        cyclic.insert('e', vec![('b', 1)]);
+        assert!(matches!(
+            topo_sort(&cyclic, ['a']),
+            Err('b' | 'c' | 'd' | 'e')
+        ));
+    }
+
+    #[test]
+    fn test_held_karp() {
+        // 4 cities on a line at 0, 1, 5 and 10
+        let at = [0u32, 1, 5, 10];
+        let dist = |a: usize, b: usize| Some(at[a].abs_diff(at[b]));
+
+        let shortest = held_karp(4, dist, Objective::Min, Tour::Open).unwrap();
+        assert_eq!(shortest.cost, 10);
+        assert!(shortest.nodes == [0, 1, 2, 3] || shortest.nodes == [3, 2, 1, 0]);
+        assert_eq!(
+            held_karp(4, dist, Objective::Min, Tour::Closed)
+                .unwrap()
+                .cost,
+            20
+        );
+        assert_eq!(
+            held_karp(4, dist, Objective::Max, Tour::Open).unwrap().cost,
+            24
+        );
+
+        let longest = held_karp(4, dist, Objective::Max, Tour::Closed).unwrap();
+        assert_eq!(longest.nodes[0], 0);
+        assert_eq!(
+            longest.cost,
+            longest
+                .nodes
+                .iter()
+                .zip(longest.nodes.iter().cycle().skip(1))
+                .map(|(&a, &b)| at[a].abs_diff(at[b]))
+                .sum::<u32>()
+        );
+
+        // without an edge between 0 and 1, 2 has to be in the middle
+        let cost = |a: usize, b: usize| (a + b != 1).then_some(1);
+        let path = held_karp(3, cost, Objective::Min, Tour::Open).unwrap();
+        assert_eq!(path.nodes[1], 2);
+        assert_eq!(held_karp(3, cost, Objective::Min, Tour::Closed), None);
+        assert_eq!(held_karp(0, dist, Objective::Min, Tour::Open), None);
+    }
+}
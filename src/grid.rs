@@ -0,0 +1,387 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    iter::successors,
+    ops::{Index, IndexMut},
+};
+
+use crate::ParseError;
+
+/// A cell of a [`Grid`] as `(x, y)`, with `y` growing downwards.
+pub type Pos = (usize, usize);
+
+/// A step between cells as `(dx, dy)`.
+pub type Dir = (isize, isize);
+
+pub const UP: Dir = (0, -1);
+pub const RIGHT: Dir = (1, 0);
+pub const DOWN: Dir = (0, 1);
+pub const LEFT: Dir = (-1, 0);
+
+/// The orthogonal directions, clockwise from up.
+pub const DIRS4: [Dir; 4] = [UP, RIGHT, DOWN, LEFT];
+
+/// The orthogonal and diagonal directions, clockwise from up.
+pub const DIRS8: [Dir; 8] = [UP, (1, -1), RIGHT, (1, 1), DOWN, (-1, 1), LEFT, (-1, -1)];
+
+/// A dense, rectangular grid stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Self {
+        self.remap(self.height, self.width, |(x, y)| (y, x))
+    }
+
+    /// Rotates a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        self.remap(self.height, self.width, |(x, y)| (y, self.height - 1 - x))
+    }
+
+    /// Rotates a quarter turn counterclockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        self.remap(self.height, self.width, |(x, y)| (self.width - 1 - y, x))
+    }
+
+    /// Builds a `width` by `height` grid whose cell at `pos` is the one of
+    /// `self` at `source(pos)`.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(Pos) -> Pos) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|pos| self[source(pos)].clone())
+            .collect();
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses one cell per character and one row per line, failing on
+    /// characters `cell` rejects and on rows of different lengths.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+
+        for (i, line) in input.lines().enumerate() {
+            let start = cells.len();
+            for (j, c) in line.char_indices() {
+                let t = cell(c).ok_or_else(|| {
+                    ParseError::at(i, line, &line[j..], format!("unexpected {c:?}"))
+                })?;
+                cells.push(t);
+            }
+
+            let row_len = cells.len() - start;
+            match width {
+                None => width = Some(row_len),
+                Some(w) if w != row_len => {
+                    return Err(ParseError::at(
+                        i,
+                        line,
+                        "",
+                        format!("expected {w} cells, found {row_len}"),
+                    ))
+                }
+                _ => {}
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[self.index(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let i = self.index(pos);
+        Some(&mut self.cells[i])
+    }
+
+    /// All cells, row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// The cells of column `x`, top to bottom. Panics if `x` is off the grid,
+    /// like `row`.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            x < self.width,
+            "column {x} is off a grid {} wide",
+            self.width
+        );
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// The cell one step from `pos` towards `dir`, if it's on the grid.
+    pub fn step(&self, (x, y): Pos, (dx, dy): Dir) -> Option<Pos> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The cells from `pos` towards `dir` up to the edge, `pos` excluded.
+    pub fn ray(&self, pos: Pos, dir: Dir) -> impl Iterator<Item = Pos> + '_ {
+        successors(self.step(pos, dir), move |&p| self.step(p, dir))
+    }
+
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS4.into_iter().filter_map(move |d| self.step(pos, d))
+    }
+
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS8.into_iter().filter_map(move |d| self.step(pos, d))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn index(&self, (x, y): Pos) -> usize {
+        x + y * self.width
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of {}x{}", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of {width}x{height}"))
+    }
+}
+
+/// Prints one line per row, cells side by side.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A cell of a [`SparseGrid`], which may lie at negative coordinates.
+pub type Point = (isize, isize);
+
+/// An unbounded grid that only stores the cells that were set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many cells are set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, pos: Point) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Point) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn insert(&mut self, pos: Point, cell: T) -> Option<T> {
+        self.cells.insert(pos, cell)
+    }
+
+    pub fn entry(&mut self, pos: Point) -> Entry<'_, Point, T> {
+        self.cells.entry(pos)
+    }
+
+    /// The cells that are set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&pos, cell)| (pos, cell))
+    }
+
+    /// The smallest and largest coordinates of the cells that are set.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.cells.keys().fold(None, |bounds, &(x, y)| {
+            let ((x0, y0), (x1, y1)) = bounds.unwrap_or(((x, y), (x, y)));
+            Some(((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y))))
+        })
+    }
+
+    pub fn neighbours4((x, y): Point) -> impl Iterator<Item = Point> {
+        DIRS4.into_iter().map(move |(dx, dy)| (x + dx, y + dy))
+    }
+
+    pub fn neighbours8((x, y): Point) -> impl Iterator<Item = Point> {
+        DIRS8.into_iter().map(move |(dx, dy)| (x + dx, y + dy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "abc\ndef";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), INPUT);
+
+        let e = Grid::parse("12\n3x", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 2));
+        let e = Grid::parse("ab\nc", Some).unwrap_err();
+        assert_eq!(
+            (e.line, e.reason.as_str()),
+            (2, "expected 2 cells, found 1")
+        );
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(
+            grid.ray((0, 0), RIGHT).collect::<Vec<_>>(),
+            [(1, 0), (2, 0)]
+        );
+        assert_eq!(grid.ray((0, 0), UP).count(), 0);
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours8((1, 0)).count(), 5);
+        assert_eq!(
+            grid.iter().find(|&(_, &c)| c == 'e').map(|(pos, _)| pos),
+            Some((1, 1))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is off a grid 3 wide")]
+    fn test_column_out_of_range() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+        grid.column(3).for_each(drop);
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.map(|c| c.to_ascii_uppercase()).to_string(), "ABC\nDEF");
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert((-2, 1), 'a');
+        *grid.entry((3, -1)).or_insert('b') = 'c';
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.get((3, -1)), Some(&'c'));
+        assert_eq!(grid.bounds(), Some(((-2, -1), (3, 1))));
+        assert!(SparseGrid::<char>::neighbours8((0, 0)).any(|p| p == (-1, -1)));
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    grid::{Grid, SparseGrid},
+    Error,
+};
+
+pub type Rgb = [u8; 3];
+
+/// The netpbm formats, all written in their binary flavour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Black and white, `.pbm`.
+    Bitmap,
+    /// Greyscale, `.pgm`.
+    Greymap,
+    /// Colour, `.ppm`.
+    Pixmap,
+}
+
+impl Format {
+    pub fn of(path: &Path) -> Result<Self, Error> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("pbm") => Ok(Self::Bitmap),
+            Some("pgm") => Ok(Self::Greymap),
+            Some("ppm") => Ok(Self::Pixmap),
+            _ => Err(format!("{} is not a .pbm, .pgm or .ppm file", path.display()).into()),
+        }
+    }
+}
+
+/// A picture of a puzzle, one pixel per cell.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pixels: Grid<Rgb>,
+}
+
+impl Image {
+    pub fn new(pixels: Grid<Rgb>) -> Self {
+        Self { pixels }
+    }
+
+    /// White where `grid` is set, black elsewhere.
+    pub fn bitmap(grid: &Grid<bool>) -> Self {
+        Self::new(grid.map(|&on| if on { [255; 3] } else { [0; 3] }))
+    }
+
+    /// Shades of grey from black for 0 up to white for the largest value.
+    pub fn greyscale<T: Copy + Into<u64>>(grid: &Grid<T>) -> Self {
+        let max = grid.cells().iter().map(|&n| n.into()).max().unwrap_or(0);
+        Self::new(grid.map(|&n| [scale(n.into(), max); 3]))
+    }
+
+    /// Black for 0, then through red and yellow up to white for the largest
+    /// value.
+    pub fn heatmap<T: Copy + Into<u64>>(grid: &Grid<T>) -> Self {
+        let max = grid.cells().iter().map(|&n| n.into()).max().unwrap_or(0);
+        Self::new(grid.map(|&n| {
+            // three channels filled one after the other
+            let heat = u64::from(scale(n.into(), max)) * 3;
+            let channel = |i: u64| heat.saturating_sub(i * 255).min(255) as u8;
+            [channel(0), channel(1), channel(2)]
+        }))
+    }
+
+    /// The bounding box of the cells of `grid` that are set, as a heatmap.
+    pub fn sparse_heatmap<T: Copy + Into<u64>>(grid: &SparseGrid<T>) -> Self {
+        let Some(((x0, y0), (x1, y1))) = grid.bounds() else {
+            return Self::new(Grid::new(0, 0, [0; 3]));
+        };
+        let (width, height) = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+
+        let mut dense = Grid::new(width, height, 0);
+        for ((x, y), &n) in grid.iter() {
+            dense[((x - x0) as usize, (y - y0) as usize)] = n.into();
+        }
+
+        Self::heatmap(&dense)
+    }
+
+    pub fn pixels(&self) -> &Grid<Rgb> {
+        &self.pixels
+    }
+
+    pub fn write(&self, w: &mut impl Write, format: Format) -> io::Result<()> {
+        let (width, height) = (self.pixels.width(), self.pixels.height());
+        let grey = |&[r, g, b]: &Rgb| ((u16::from(r) + u16::from(g) + u16::from(b)) / 3) as u8;
+
+        match format {
+            Format::Bitmap => {
+                writeln!(w, "P4\n{width} {height}")?;
+                // 1 is black, rows are padded to whole bytes
+                for row in self.pixels.rows() {
+                    for byte in row.chunks(8) {
+                        let bits = byte
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, p)| grey(p) < 128)
+                            .fold(0, |acc, (i, _)| acc | 0x80 >> i);
+                        w.write_all(&[bits])?;
+                    }
+                }
+            }
+            Format::Greymap => {
+                writeln!(w, "P5\n{width} {height}\n255")?;
+                let bytes: Vec<_> = self.pixels.cells().iter().map(grey).collect();
+                w.write_all(&bytes)?;
+            }
+            Format::Pixmap => {
+                writeln!(w, "P6\n{width} {height}\n255")?;
+                w.write_all(self.pixels.cells().as_flattened())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the image in the format its extension names.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let format = Format::of(path)?;
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write(&mut w, format)?;
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// `n` out of `max` as a byte.
+fn scale(n: u64, max: u64) -> u8 {
+    match max {
+        0 => 0,
+        _ => (u128::from(n) * 255 / u128::from(max)) as u8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(image: &Image, format: Format) -> Vec<u8> {
+        let mut out = vec![];
+        image.write(&mut out, format).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_formats() {
+        let grid = Grid::parse("#.........\n.#########", |c| Some(c == '#')).unwrap();
+        let image = Image::bitmap(&grid);
+
+        assert_eq!(
+            written(&image, Format::Bitmap),
+            b"P4\n10 2\n\x7f\xc0\x80\x00"
+        );
+        assert_eq!(
+            &written(&image, Format::Greymap)[..13],
+            b"P5\n10 2\n255\n\xff"
+        );
+        let pixmap = written(&image, Format::Pixmap);
+        assert_eq!(pixmap.len(), "P6\n10 2\n255\n".len() + 10 * 2 * 3);
+        assert!(pixmap.ends_with(&[255; 3]));
+
+        assert_eq!(Format::of(Path::new("out.ppm")).unwrap(), Format::Pixmap);
+        assert!(Format::of(Path::new("out.png")).is_err());
+    }
+
+    #[test]
+    fn test_scales() {
+        let grid = Grid::parse("0123", |c| c.to_digit(10)).unwrap();
+        assert_eq!(
+            Image::greyscale(&grid).pixels().row(0),
+            [[0; 3], [85; 3], [170; 3], [255; 3]]
+        );
+        assert_eq!(
+            Image::heatmap(&grid).pixels().row(0),
+            [[0, 0, 0], [255, 0, 0], [255, 255, 0], [255, 255, 255]]
+        );
+
+        let mut sparse = SparseGrid::new();
+        sparse.insert((-1, 0), 2u32);
+        sparse.insert((1, 1), 1);
+        let pixels = Image::sparse_heatmap(&sparse).pixels().clone();
+        assert_eq!((pixels.width(), pixels.height()), (3, 2));
+        assert_eq!(pixels[(0, 0)], [255; 3]);
+        assert_eq!(pixels[(1, 0)], [0; 3]);
+    }
+}
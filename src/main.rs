@@ -1,5 +1,215 @@
-pub mod y2023;
+use std::{env, path::Path, process};
 
-fn main() -> Result<(), aoc::Error> {
-    y2023::day08::solve()
+use aoc::{
+    bench::{append_history, bench, HISTORY_PATH},
+    check::{check, load_answers, Status},
+    days_of,
+    fetch::Fetcher,
+    find_day,
+    image::Format,
+    pull_input,
+    scaffold::new_day,
+    Day, Error,
+};
+
+const USAGE: &str = "Usage: main [all | YEAR [DAY [PART]]]
+       main YEAR DAY [PART] --render FILE.{pbm,pgm,ppm}
+       main check [YEAR [DAY]]
+       main bench [YEAR [DAY]] [--runs N]
+       main fetch YEAR DAY
+       main new YEAR DAY [TITLE]";
+
+const DEFAULT_RUNS: usize = 5;
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args[..] {
+        [] => {
+            let latest = aoc::days().last();
+            latest.ok_or("no days registered".to_string())?.solve(None)
+        }
+        ["all"] => solve_all(select(&[])?),
+        ["check", ref rest @ ..] => check_all(select(rest)?),
+        ["bench", ref rest @ ..] => match *rest {
+            [ref days @ .., "--runs", runs] => bench_all(select(days)?, parse_arg("runs", runs)?),
+            _ => bench_all(select(rest)?, DEFAULT_RUNS),
+        },
+        ["fetch", year, day] => {
+            let fetcher = Fetcher::from_env();
+            let (year, day) = (parse_arg("year", year)?, parse_arg("day", day)?);
+            fetcher.input(year, day)?;
+            println!("{}", fetcher.path(year, day).display());
+            Ok(())
+        }
+        ["new", year, day, ref title @ ..] if title.len() <= 1 => {
+            let (year, day) = (parse_arg("year", year)?, parse_arg("day", day)?);
+            let title = title
+                .first()
+                .map_or_else(|| format!("Day {day}"), |t| t.to_string());
+            scaffold(year, day, &title)
+        }
+        [year, day, ref part @ .., "--render", path] if part.len() <= 1 => {
+            // fail on a bad extension before spending a solve on it
+            Format::of(Path::new(path))?;
+            let day = find_day(parse_arg("year", year)?, parse_arg("day", day)?)?;
+            let part = part.first().map(|p| parse_arg("part", p)).transpose()?;
+            day.solve(part)?;
+            render(day, Path::new(path))
+        }
+        [year] => solve_all(select(&[year])?),
+        [year, day] => find_day(parse_arg("year", year)?, parse_arg("day", day)?)?.solve(None),
+        [year, day, part] => find_day(parse_arg("year", year)?, parse_arg("day", day)?)?
+            .solve(Some(parse_arg("part", part)?)),
+        _ => Err(USAGE.to_string().into()),
+    }
+}
+
+/// Resolves `[YEAR [DAY]]` to the registered days it designates.
+fn select(args: &[&str]) -> Result<Vec<&'static Day>, Error> {
+    match *args {
+        [] => Ok(aoc::days().collect()),
+        [year] => Ok(days_of(parse_arg("year", year)?)?.iter().collect()),
+        [year, day] => Ok(vec![find_day(
+            parse_arg("year", year)?,
+            parse_arg("day", day)?,
+        )?]),
+        _ => Err(USAGE.to_string().into()),
+    }
+}
+
+/// Solves every day in `days`, reporting the days that fail and moving on to
+/// the next one.
+fn solve_all(days: Vec<&Day>) -> Result<(), Error> {
+    let mut failed = 0;
+
+    for day in days {
+        println!("== {} day {:02}: {} ==", day.year, day.day, day.title);
+        if let Err(e) = day.solve(None) {
+            eprintln!("{} day {:02} failed: {e}", day.year, day.day);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{failed} days failed").into());
+    }
+
+    Ok(())
+}
+
+fn check_all(days: Vec<&Day>) -> Result<(), Error> {
+    let (mut passed, mut failed, mut errors, mut missing) = (0, 0, 0, 0);
+    let mut answers = None;
+
+    for day in days {
+        let known = match &answers {
+            Some((year, known)) if *year == day.year => known,
+            _ => &answers.insert((day.year, load_answers(day.year)?)).1,
+        };
+        let expected = known.get(&day.day).cloned().unwrap_or_default();
+
+        let checked = match check(day, &expected) {
+            Ok(checked) => checked,
+            Err(e) => {
+                eprintln!("{} day {:02}: {e}", day.year, day.day);
+                errors += 2;
+                continue;
+            }
+        };
+
+        for (part, checked) in checked.iter().enumerate() {
+            let prefix = format!("{} day {:02} part {}", day.year, day.day, part + 1);
+            match checked {
+                Ok((answer, status)) => {
+                    println!("{prefix}: {answer} ({status})");
+                    match status {
+                        Status::Pass => passed += 1,
+                        Status::Fail { .. } => failed += 1,
+                        Status::Missing => missing += 1,
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{prefix}: {e}");
+                    errors += 1;
+                }
+            }
+        }
+    }
+
+    println!("{passed} passed, {failed} failed, {errors} errors, {missing} missing");
+    if failed + errors > 0 {
+        return Err(format!("check failed: {failed} mismatched answers, {errors} errors").into());
+    }
+
+    Ok(())
+}
+
+fn bench_all(days: Vec<&Day>, runs: usize) -> Result<(), Error> {
+    let mut results = Vec::with_capacity(days.len());
+    let mut failed = 0;
+
+    for day in days {
+        let result = match bench(day, runs) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("{} day {:02} failed: {e}", day.year, day.day);
+                failed += 1;
+                continue;
+            }
+        };
+        for (stage, stats) in &result.stages {
+            println!(
+                "{} day {:02} {stage}: min {:.2?}, median {:.2?}, mean {:.2?}",
+                day.year, day.day, stats.min, stats.median, stats.mean
+            );
+        }
+        results.push(result);
+    }
+
+    append_history(HISTORY_PATH, &results)?;
+    println!("{runs} runs each, appended to {HISTORY_PATH}");
+    if failed > 0 {
+        return Err(format!("{failed} days failed").into());
+    }
+
+    Ok(())
+}
+
+fn render(day: &Day, path: &Path) -> Result<(), Error> {
+    let input = pull_input(day.year, day.day)?;
+    let image = (day.render)(&input)?
+        .ok_or_else(|| format!("{} day {:02} has nothing to render", day.year, day.day))?;
+    image.save(path)?;
+    println!("wrote {}", path.display());
+
+    Ok(())
+}
+
+fn scaffold(year: usize, day: usize, title: &str) -> Result<(), Error> {
+    for path in new_day(Path::new("src"), year, day, title)? {
+        println!("wrote {}", path.display());
+    }
+
+    let fetcher = Fetcher::from_env();
+    match fetcher.input(year, day) {
+        Ok(_) => println!("wrote {}", fetcher.path(year, day).display()),
+        Err(e) => eprintln!("warning: {e}"),
+    }
+    println!("run with: cargo run -- {year} {day}");
+
+    Ok(())
+}
+
+fn parse_arg(name: &str, arg: &str) -> Result<usize, Error> {
+    arg.parse()
+        .map_err(|_| format!("invalid {name} {arg:?}\n{USAGE}").into())
 }
@@ -0,0 +1,230 @@
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, or `None` if it doesn't fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The gcd of all of `ns`, 0 if there are none.
+pub fn gcd_all(ns: &[u64]) -> u64 {
+    ns.iter().fold(0, |acc, &n| gcd(acc, n))
+}
+
+/// The lcm of all of `ns`, 1 if there are none, or `None` on overflow.
+pub fn lcm_all(ns: &[u64]) -> Option<u64> {
+    ns.iter().try_fold(1, |acc, &n| lcm(acc, n))
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g`, where `g` is the
+/// non-negative gcd of `a` and `b`.
+///
+/// # Panics
+///
+/// If the gcd is 2^63, which doesn't fit in an `i64`: when `a` and `b` are
+/// both `i64::MIN` or 0, but not both 0.
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = egcd(a.into(), b.into());
+    let g = i64::try_from(g).expect("gcd must fit in an i64");
+    (g, x as i64, y as i64)
+}
+
+fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inv(a: i64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    inverse(a.into(), m.into()).map(|x| x as u64)
+}
+
+fn inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solves the system `x ≡ a (mod m)` for each `(a, m)` of `congruences`,
+/// whose moduli need not be coprime. Returns the smallest non-negative `x`
+/// with the lcm of the moduli, all solutions being `x` plus its multiples,
+/// or `None` if the congruences contradict each other or the lcm overflows.
+///
+/// # Panics
+///
+/// If a modulus is 0.
+pub fn crt(congruences: &[(i64, u64)]) -> Option<(u64, u64)> {
+    congruences.iter().try_fold((0, 1), |(x, m), &(a, n)| {
+        assert!(n > 0, "modulus must be positive");
+
+        let g = gcd(m, n);
+        let diff = i128::from(a) - i128::from(x);
+        if diff.rem_euclid(g.into()) != 0 {
+            return None;
+        }
+
+        // x + m * k ≡ a (mod n), that is (m / g) * k ≡ diff / g (mod n / g)
+        let step = i128::from(n / g);
+        let rhs = (diff / i128::from(g)).rem_euclid(step) as u128;
+        let inv = inverse((m / g).into(), step)? as u128;
+        let k = rhs * inv % step as u128;
+
+        let l = (m / g).checked_mul(n)?;
+        let x = (u128::from(x) + u128::from(m) * k) % u128::from(l);
+        Some((x as u64, l))
+    })
+}
+
+/// `base` to the power of `exp`, modulo `m`.
+///
+/// # Panics
+///
+/// If `m` is 0.
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    assert!(m > 0, "modulus must be positive");
+    let m = u128::from(m);
+    let mut base = u128::from(base) % m;
+    let mut result = 1 % m;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+
+    result as u64
+}
+
+/// The largest `r` such that `r * r <= n`.
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+
+    // Newton's method from above converges down to the floor of the root
+    let mut x = 1 << (n.ilog2() / 2 + 1);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(gcd_all(&[12, 18, 30]), 6);
+        assert_eq!(lcm_all(&[4, 6, 10]), Some(60));
+        assert_eq!(lcm_all(&[u64::MAX, 2]), None);
+        assert_eq!(ext_gcd(240, 46), (2, -9, 47));
+        assert_eq!(ext_gcd(i64::MIN, 1), (1, 0, 1));
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(4, 12), None);
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(isqrt(u64::MAX), u64::from(u32::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "gcd must fit in an i64")]
+    fn test_ext_gcd_overflow() {
+        ext_gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be positive")]
+    fn test_mod_pow_zero() {
+        mod_pow(2, 3, 0);
+    }
+
+    proptest! {
+        #[test]
+        fn test_gcd_lcm(a in 1..1u64 << 32, b in 1..1u64 << 32) {
+            let g = gcd(a, b);
+            prop_assert_eq!((a % g, b % g), (0, 0));
+            prop_assert_eq!(gcd(a / g, b / g), 1);
+            let product = u128::from(a) * u128::from(b);
+            prop_assert_eq!(lcm(a, b).map(|l| u128::from(l) * u128::from(g)), Some(product));
+        }
+
+        #[test]
+        fn test_ext_gcd(a in -1i64 << 40..1 << 40, b in -1i64 << 40..1 << 40) {
+            let (g, x, y) = ext_gcd(a, b);
+            prop_assert_eq!(g as u64, gcd(a.unsigned_abs(), b.unsigned_abs()));
+            let combination = i128::from(a) * i128::from(x) + i128::from(b) * i128::from(y);
+            prop_assert_eq!(combination, i128::from(g));
+        }
+
+        #[test]
+        fn test_mod_inv(a in any::<i64>(), m in 1..u64::MAX) {
+            match mod_inv(a, m) {
+                Some(x) => {
+                    prop_assert!(x < m);
+                    let product = i128::from(a) * i128::from(x);
+                    prop_assert_eq!(product.rem_euclid(m.into()), 1 % i128::from(m));
+                }
+                None => prop_assert_ne!(gcd(a.unsigned_abs(), m), 1),
+            }
+        }
+
+        #[test]
+        fn test_crt(congruences in prop::collection::vec((-50i64..50, 1u64..30), 0..4)) {
+            let moduli: Vec<_> = congruences.iter().map(|&(_, m)| m).collect();
+            let solves = |x: u64| {
+                congruences
+                    .iter()
+                    .all(|&(a, m)| (x as i64 - a).rem_euclid(m as i64) == 0)
+            };
+            let expected = lcm_all(&moduli)
+                .and_then(|l| (0..l).find(|&x| solves(x)).map(|x| (x, l)));
+            prop_assert_eq!(crt(&congruences), expected);
+        }
+
+        #[test]
+        fn test_mod_pow(base in any::<u64>(), exp in 0..200u64, m in 1..u64::MAX) {
+            let m128 = u128::from(m);
+            let naive = (0..exp).fold(1 % m128, |acc, _| acc * u128::from(base % m) % m128);
+            prop_assert_eq!(u128::from(mod_pow(base, exp, m)), naive);
+        }
+
+        #[test]
+        fn test_isqrt(n in any::<u64>()) {
+            let r = u128::from(isqrt(n));
+            prop_assert!(r * r <= n.into() && (r + 1) * (r + 1) > n.into());
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::str::FromStr;
+
+use nom::{
+    character::complete::{digit1, one_of, space0, space1},
+    combinator::{all_consuming, map_res, opt, recognize},
+    multi::separated_list0,
+    sequence::{delimited, pair},
+    IResult, Parser,
+};
+
+use crate::ParseError;
+
+/// An unsigned integer.
+pub fn uint<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// An integer with an optional sign.
+pub fn int<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// Integers separated by spaces, leading and trailing spaces included.
+pub fn nums<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    delimited(space0, separated_list0(space1, int), space0)(input)
+}
+
+/// A line of the input with its 0-based index, so that errors can point at it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Line<'a> {
+    pub index: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Runs `parser` on `s`, a subslice of the line, which must be consumed
+    /// entirely.
+    pub fn parse_at<T>(
+        self,
+        s: &'a str,
+        parser: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+    ) -> Result<T, ParseError> {
+        all_consuming(parser)(s)
+            .map(|(_, t)| t)
+            .map_err(|e| ParseError::nom(self.index, self.text, e))
+    }
+
+    /// Runs `parser` on the whole line.
+    pub fn parse<T>(
+        self,
+        parser: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+    ) -> Result<T, ParseError> {
+        self.parse_at(self.text, parser)
+    }
+
+    /// An error pointing at `rest`, a subslice of the line.
+    pub fn error(self, rest: &str, reason: impl Into<String>) -> ParseError {
+        ParseError::at(self.index, self.text, rest, reason)
+    }
+
+    /// Splits a `key: value` line.
+    pub fn key_value(self) -> Result<(&'a str, &'a str), ParseError> {
+        let (key, value) = self
+            .text
+            .split_once(':')
+            .ok_or_else(|| self.error("", "expected `key: value`"))?;
+        Ok((key, value.trim_start()))
+    }
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, text)| Line { index, text })
+}
+
+/// Runs `parser` on every non-empty line.
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> Result<Vec<T>, ParseError> {
+    lines(input)
+        .filter(|l| !l.text.is_empty())
+        .map(|l| l.parse(&mut parser))
+        .collect()
+}
+
+/// Groups of consecutive lines separated by blank lines.
+pub fn blocks(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut blocks = vec![];
+    let mut block = vec![];
+
+    for line in lines(input) {
+        if line.text.trim().is_empty() {
+            if !block.is_empty() {
+                blocks.push(block);
+                block = vec![];
+            }
+        } else {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// The `key: value` pairs of `lines`.
+pub fn key_values<'a>(lines: &[Line<'a>]) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    lines.iter().map(|l| l.key_value()).collect()
+}
+
+/// A block that starts with a `label:` line, like `seed-to-soil map:`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Section<'a> {
+    pub label: &'a str,
+    pub lines: Vec<Line<'a>>,
+}
+
+impl<'a> Section<'a> {
+    pub fn of(block: &[Line<'a>]) -> Result<Self, ParseError> {
+        let (header, lines) = block
+            .split_first()
+            .ok_or_else(|| ParseError::new(1, 1, "", "expected a section"))?;
+        let label = header
+            .text
+            .strip_suffix(':')
+            .ok_or_else(|| header.error("", "expected a `label:` header"))?;
+
+        Ok(Self {
+            label,
+            lines: lines.to_vec(),
+        })
+    }
+}
+
+/// Splits `input` into labelled sections, one per block.
+pub fn sections(input: &str) -> Result<Vec<Section<'_>>, ParseError> {
+    blocks(input).iter().map(|b| Section::of(b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{bytes::complete::tag, sequence::tuple};
+
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(uint::<u16>("65535 x"), Ok((" x", 65535)));
+        assert!(uint::<u16>("65536").is_err());
+        assert!(uint::<u8>("-1").is_err());
+        assert_eq!(int::<i64>("-12,"), Ok((",", -12)));
+        assert_eq!(int::<i64>("+7"), Ok(("", 7)));
+        assert_eq!(nums::<usize>("  41 48  6"), Ok(("", vec![41, 48, 6])));
+        assert_eq!(nums::<usize>(" 1 2 | 3"), Ok(("| 3", vec![1, 2])));
+    }
+
+    #[test]
+    fn test_line() {
+        let line = lines("a\nCard 1: 4 x").nth(1).unwrap();
+
+        let card = tuple((tag("Card "), uint::<usize>, tag(":"), nums::<usize>));
+        let e = line.parse(card).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 11));
+
+        assert_eq!(line.key_value(), Ok(("Card 1", "4 x")));
+        assert!(lines("x").next().unwrap().key_value().is_err());
+
+        assert_eq!(parse_lines("1\n\n-2\n", int::<i32>).unwrap(), vec![1, -2]);
+        assert_eq!(parse_lines("1\nx", int::<i32>).unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "seeds: 1 2\n\nsoil map:\n1 2 3\n4 5 6\n\n\nwater map:\n";
+        let blocks = blocks(input);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(key_values(&blocks[0]).unwrap(), [("seeds", "1 2")]);
+
+        let soil = Section::of(&blocks[1]).unwrap();
+        assert_eq!(soil.label, "soil map");
+        assert_eq!(
+            soil.lines[1],
+            Line {
+                index: 4,
+                text: "4 5 6"
+            }
+        );
+        assert_eq!(Section::of(&blocks[2]).unwrap().lines, []);
+
+        let e = sections(input).unwrap_err();
+        assert_eq!(
+            (e.line, e.reason.as_str()),
+            (1, "expected a `label:` header")
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::ops::Range;
+
+/// A set of integers stored as sorted, disjoint ranges, none of which are
+/// empty or touch each other.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet {
+    ranges: Vec<Range<usize>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ranges of the set, in order.
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    /// How many integers are in the set.
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|r| r.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        self.ranges.last().map(|r| r.end - 1)
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.contains(&value))
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn insert(&mut self, range: Range<usize>) {
+        *self = self.union(&Self::from(range));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            // the range that ends first can't overlap anything further
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let mut others = other.ranges.iter().peekable();
+
+        for a in &self.ranges {
+            let mut start = a.start;
+            while let Some(b) = others.next_if(|b| b.end <= a.end) {
+                if b.start > start {
+                    ranges.push(start..b.start);
+                }
+                start = start.max(b.end);
+            }
+            // a range of `other` may still cut into the end of `a`
+            let end = others.peek().map_or(a.end, |b| b.start.clamp(start, a.end));
+            if start < end {
+                ranges.push(start..end);
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Moves every integer of the set by `offset`.
+    ///
+    /// # Panics
+    ///
+    /// If an integer would leave the range of `usize`.
+    pub fn shift(&self, offset: isize) -> Self {
+        let shift = |n: usize| {
+            n.checked_add_signed(offset)
+                .unwrap_or_else(|| panic!("{n} shifted by {offset} overflows"))
+        };
+
+        Self {
+            ranges: self
+                .ranges
+                .iter()
+                .map(|r| shift(r.start)..shift(r.end))
+                .collect(),
+        }
+    }
+}
+
+impl From<Range<usize>> for RangeSet {
+    fn from(range: Range<usize>) -> Self {
+        Self::from_iter([range])
+    }
+}
+
+/// Merges ranges that overlap or touch, in any order.
+impl FromIterator<Range<usize>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<usize>>>(iter: I) -> Self {
+        let mut sorted: Vec<_> = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        sorted.sort_by_key(|r| r.start);
+
+        let mut ranges: Vec<Range<usize>> = Vec::with_capacity(sorted.len());
+        for r in sorted {
+            match ranges.last_mut() {
+                Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+                _ => ranges.push(r),
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_iter() {
+        let set: RangeSet = [5..8, 1..3, 3..4, 10..10, 6..12].into_iter().collect();
+        assert_eq!(set.ranges(), [1..4, 5..12]);
+        assert_eq!(set.len(), 10);
+        assert_eq!((set.min(), set.max()), (Some(1), Some(11)));
+        assert!(set.contains(3) && !set.contains(4) && set.contains(11));
+        assert!(RangeSet::from(7..7).is_empty());
+    }
+
+    #[test]
+    fn test_algebra() {
+        let a: RangeSet = [0..10, 20..30].into_iter().collect();
+        let b: RangeSet = [5..25, 28..29, 40..50].into_iter().collect();
+
+        assert_eq!(a.union(&b).ranges(), [0..30, 40..50]);
+        assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25, 28..29]);
+        assert_eq!(a.difference(&b).ranges(), [0..5, 25..28, 29..30]);
+        assert_eq!(b.difference(&a).ranges(), [10..20, 40..50]);
+        assert_eq!(a.shift(5).shift(-3).ranges(), [2..12, 22..32]);
+        assert!(RangeSet::from(22..24).is_subset(&a));
+        assert!(!b.is_subset(&a));
+
+        let mut c = RangeSet::new();
+        c.insert(3..5);
+        c.insert(0..3);
+        assert_eq!(c, RangeSet::from(0..5));
+    }
+
+    #[test]
+    fn test_against_sets() {
+        use std::collections::BTreeSet;
+
+        let sets: Vec<RangeSet> = [
+            vec![],
+            vec![0..2, 2..4],
+            vec![1..2, 3..7, 9..12],
+            vec![0..1, 2..3, 4..5, 6..7, 8..9],
+            vec![2..10, 11..13],
+        ]
+        .into_iter()
+        .map(RangeSet::from_iter)
+        .collect();
+        let values = |s: &RangeSet| {
+            s.ranges()
+                .iter()
+                .cloned()
+                .flatten()
+                .collect::<BTreeSet<_>>()
+        };
+
+        for a in &sets {
+            for b in &sets {
+                let (va, vb) = (values(a), values(b));
+                assert_eq!(values(&a.union(b)), &va | &vb);
+                assert_eq!(values(&a.intersection(b)), &va & &vb);
+                assert_eq!(values(&a.difference(b)), &va - &vb);
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::Error;
+
+const DAY_TEMPLATE: &str = include_str!("../dayNN.rs.tpl");
+
+const YEAR_TEMPLATE: &str = "pub const DAYS: &[crate::Day] = &[];\n";
+
+const YEARS_DECL: &str = "pub const YEARS: &[&[Day]] = &[";
+
+const DAYS_DECL: &str = "pub const DAYS: &[crate::Day] = &[";
+
+/// Creates `src/yYYYY/dayNN.rs` from the template and registers it, creating
+/// and registering the year module too when it doesn't exist yet.
+///
+/// Registration is idempotent, but an existing day is never overwritten.
+/// Returns the files that were created or modified.
+pub fn new_day(src: &Path, year: usize, day: usize, title: &str) -> Result<Vec<PathBuf>, Error> {
+    if year < 2015 || !(1..=25).contains(&day) {
+        return Err(format!("there is no puzzle for {year} day {day}").into());
+    }
+
+    let year_dir = src.join(format!("y{year}"));
+    let day_path = year_dir.join(format!("day{day:02}.rs"));
+    if day_path.exists() {
+        return Err(format!("{} already exists", day_path.display()).into());
+    }
+
+    let mut touched = Vec::new();
+
+    let mod_path = year_dir.join("mod.rs");
+    if !mod_path.exists() {
+        fs::create_dir_all(&year_dir)?;
+        fs::write(&mod_path, YEAR_TEMPLATE)?;
+
+        let lib_path = src.join("aoc.rs");
+        let year_mod = format!("y{year}");
+        edit(&lib_path, |s| {
+            let s = add_mod(s, "y", &year_mod)?;
+            add_entry(&s, YEARS_DECL, &format!("{year_mod}::DAYS"))
+        })?;
+        touched.push(lib_path);
+    }
+
+    let day_mod = format!("day{day:02}");
+    edit(&mod_path, |s| {
+        let s = add_mod(s, "day", &day_mod)?;
+        add_entry(&s, DAYS_DECL, &format!("{day_mod}::DAY"))
+    })?;
+    touched.push(mod_path);
+
+    let code = DAY_TEMPLATE
+        .replace("$YEAR", &year.to_string())
+        .replace("$DAY", &day.to_string())
+        .replace("$TITLE", &format!("{title:?}"));
+    fs::write(&day_path, code)?;
+    touched.push(day_path);
+
+    Ok(touched)
+}
+
+fn edit(path: &Path, f: impl FnOnce(&str) -> Result<String, Error>) -> Result<(), Error> {
+    let content = fs::read_to_string(path)?;
+    let edited = f(&content).map_err(|e| format!("{}: {e}", path.display()))?;
+    if edited != content {
+        fs::write(path, edited)?;
+    }
+
+    Ok(())
+}
+
+/// Adds `pub mod {name};` among the sorted `pub mod {prefix}NN;` declarations,
+/// or at the top of the file if there are none yet.
+fn add_mod(src: &str, prefix: &str, name: &str) -> Result<String, Error> {
+    let mut lines: Vec<&str> = src.lines().collect();
+    let decl = format!("pub mod {name};");
+
+    let siblings: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| {
+            let module = l.strip_prefix("pub mod ")?.strip_suffix(';')?;
+            let suffix = module.strip_prefix(prefix)?;
+            suffix
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+                .then_some((i, module))
+        })
+        .collect();
+
+    if siblings.iter().any(|&(_, m)| m == name) {
+        return Ok(src.into());
+    }
+
+    match siblings.iter().find(|&&(_, m)| m > name) {
+        Some(&(i, _)) => lines.insert(i, &decl),
+        None => match siblings.last() {
+            Some(&(i, _)) => lines.insert(i + 1, &decl),
+            None => lines.splice(0..0, [decl.as_str(), ""]).for_each(drop),
+        },
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `item` to the slice literal opened by the line starting with `decl`,
+/// keeping the entries sorted and unique. Single-line literals stay on one
+/// line as long as they fit.
+fn add_entry(src: &str, decl: &str, item: &str) -> Result<String, Error> {
+    let lines: Vec<&str> = src.lines().collect();
+    let start = lines
+        .iter()
+        .position(|l| l.starts_with(decl))
+        .ok_or_else(|| format!("missing `{decl}`"))?;
+    let end = (start..lines.len())
+        .find(|&i| lines[i].trim_end().ends_with("];"))
+        .ok_or_else(|| format!("unterminated `{decl}`"))?;
+
+    let body = lines[start..=end].join("\n");
+    let body = &body[decl.len()..body.trim_end().len() - "];".len()];
+    let mut items: Vec<&str> = body
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if items.contains(&item) {
+        return Ok(src.into());
+    }
+    items.push(item);
+    items.sort();
+
+    let single_line = format!("{decl}{}];", items.join(", "));
+    let literal = if start == end && single_line.len() <= 100 && items.len() > 1 {
+        single_line
+    } else {
+        let entries: String = items.iter().map(|i| format!("    {i},\n")).collect();
+        format!("{decl}\n{entries}];")
+    };
+
+    let mut result: Vec<&str> = lines[..start].to_vec();
+    result.push(&literal);
+    result.extend(&lines[end + 1..]);
+
+    Ok(result.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    const YEAR_MOD: &str = "pub mod day01;
+pub mod day03;
+
+pub const DAYS: &[crate::Day] = &[
+    day01::DAY,
+    day03::DAY,
+];
+";
+
+    #[test]
+    fn test_add_mod() {
+        let added = add_mod(YEAR_MOD, "day", "day02").unwrap();
+        assert!(added.starts_with("pub mod day01;\npub mod day02;\npub mod day03;\n\n"));
+        assert_eq!(add_mod(&added, "day", "day02").unwrap(), added);
+
+        let appended = add_mod(YEAR_MOD, "day", "day25").unwrap();
+        assert!(appended.starts_with("pub mod day01;\npub mod day03;\npub mod day25;\n\n"));
+
+        assert_eq!(
+            add_mod(YEAR_TEMPLATE, "day", "day01").unwrap(),
+            format!("pub mod day01;\n\n{YEAR_TEMPLATE}")
+        );
+    }
+
+    #[test]
+    fn test_add_entry() {
+        let added = add_entry(YEAR_MOD, DAYS_DECL, "day02::DAY").unwrap();
+        assert!(added.ends_with(
+            "= &[
+    day01::DAY,
+    day02::DAY,
+    day03::DAY,
+];
+"
+        ));
+        assert_eq!(add_entry(&added, DAYS_DECL, "day02::DAY").unwrap(), added);
+
+        let years = "pub const YEARS: &[&[Day]] = &[y2015::DAYS, y2023::DAYS];\n";
+        assert_eq!(
+            add_entry(years, YEARS_DECL, "y2022::DAYS").unwrap(),
+            "pub const YEARS: &[&[Day]] = &[y2015::DAYS, y2022::DAYS, y2023::DAYS];\n"
+        );
+
+        assert_eq!(
+            add_entry(YEAR_TEMPLATE, DAYS_DECL, "day01::DAY").unwrap(),
+            "pub const DAYS: &[crate::Day] = &[\n    day01::DAY,\n];\n"
+        );
+        assert!(add_entry("", DAYS_DECL, "day01::DAY").is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let src = env::temp_dir().join(format!("aoc-scaffold-{}", process::id()));
+        let _ = fs::remove_dir_all(&src);
+        fs::create_dir_all(&src).unwrap();
+        fs::write(
+            src.join("aoc.rs"),
+            "pub mod check;\n\npub mod y2015;\n\n\
+            pub const YEARS: &[&[Day]] = &[y2015::DAYS];\n",
+        )
+        .unwrap();
+
+        new_day(&src, 2016, 1, "No Time for a Taxicab").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(src.join("aoc.rs")).unwrap(),
+            "pub mod check;\n\npub mod y2015;\npub mod y2016;\n\n\
+            pub const YEARS: &[&[Day]] = &[y2015::DAYS, y2016::DAYS];\n"
+        );
+        assert_eq!(
+            fs::read_to_string(src.join("y2016/mod.rs")).unwrap(),
+            "pub mod day01;\n\npub const DAYS: &[crate::Day] = &[\n    day01::DAY,\n];\n"
+        );
+        assert!(fs::read_to_string(src.join("y2016/day01.rs"))
+            .unwrap()
+            .contains("crate::solve!(2016, 1, \"No Time for a Taxicab\", p1, p2);"));
+
+        // never clobber existing work
+        fs::write(src.join("y2016/day01.rs"), "solved").unwrap();
+        assert!(new_day(&src, 2016, 1, "").is_err());
+        assert_eq!(
+            fs::read_to_string(src.join("y2016/day01.rs")).unwrap(),
+            "solved"
+        );
+
+        assert!(new_day(&src, 2016, 26, "").is_err());
+
+        // titles are escaped into a valid string literal
+        new_day(&src, 2016, 2, r#"The "Bath\room" Security"#).unwrap();
+        assert!(fs::read_to_string(src.join("y2016/day02.rs"))
+            .unwrap()
+            .contains(r#"crate::solve!(2016, 2, "The \"Bath\\room\" Security", p1, p2);"#));
+
+        fs::remove_dir_all(src).unwrap();
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    hint::black_box,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{image::Image, pull_input, Error};
+
+/// The answer to one part of a puzzle.
+///
+/// Every numeric answer is widened to `i128` so that e.g. a `usize` and an
+/// `i64` holding the same value compare equal.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Answer {
+    Num(i128),
+    Text(String),
+}
+
+macro_rules! answer_from_num {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Self::Num(n as i128)
+            }
+        })*
+    };
+}
+
+answer_from_num!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.into())
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Num(n) => write!(f, "{n}"),
+            Self::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+/// Parses back what `Display` wrote: anything that looks like an integer is a
+/// number, the rest is text.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse().map_or_else(|_| Self::Text(s.into()), Self::Num))
+    }
+}
+
+/// What a part may return: anything convertible to an [`Answer`], or a
+/// `Result` of it when the part can fail.
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, Error>;
+}
+
+impl<T: Into<Answer>> IntoAnswer for T {
+    fn into_answer(self) -> Result<Answer, Error> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<Answer>> IntoAnswer for Result<T, Error> {
+    fn into_answer(self) -> Result<Answer, Error> {
+        self.map(Into::into)
+    }
+}
+
+pub trait Solution {
+    const YEAR: usize;
+    const DAY: usize;
+    const TITLE: &'static str;
+
+    /// The parsed puzzle input, which may borrow from the raw input.
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error>;
+    fn part1(input: &Self::Input<'_>) -> Result<Answer, Error>;
+    fn part2(input: &Self::Input<'_>) -> Result<Answer, Error>;
+
+    /// A picture of the puzzle, for the days that have one to show.
+    fn render(_input: &Self::Input<'_>) -> Result<Option<Image>, Error> {
+        Ok(None)
+    }
+}
+
+/// One run of a puzzle on its input, with how long each stage took.
+pub struct Run {
+    pub parse: Duration,
+    /// The answer to each part that was asked for, and how long it took.
+    pub parts: [Option<(Result<Answer, Error>, Duration)>; 2],
+}
+
+/// A registered puzzle: the type-erased form of a [`Solution`] the runner
+/// dispatches to.
+pub struct Day {
+    pub year: usize,
+    pub day: usize,
+    pub title: &'static str,
+    /// Parses the input once and runs both parts on it, or only the given one.
+    pub run: fn(&str, Option<usize>) -> Result<Run, Error>,
+    /// Parses the input and pictures it, if the day can.
+    pub render: fn(&str) -> Result<Option<Image>, Error>,
+}
+
+impl Day {
+    pub const fn of<S: Solution>() -> Self {
+        Self {
+            year: S::YEAR,
+            day: S::DAY,
+            title: S::TITLE,
+            run: run::<S>,
+            render: render::<S>,
+        }
+    }
+
+    /// Solves both parts, or only `part` when given.
+    pub fn solve(&self, part: Option<usize>) -> Result<(), Error> {
+        if let Some(n) = part.filter(|n| !(1..=2).contains(n)) {
+            return Err(format!("invalid part {n}").into());
+        }
+
+        let input = pull_input(self.year, self.day)?;
+        for (answer, _) in (self.run)(&input, part)?.parts.into_iter().flatten() {
+            println!("{}", answer?);
+        }
+
+        Ok(())
+    }
+}
+
+fn run<S: Solution>(input: &str, part: Option<usize>) -> Result<Run, Error> {
+    let tag = |e: Error| e.in_day(S::YEAR, S::DAY);
+
+    let (parsed, parse) = time(|| S::parse(input));
+    let parsed = parsed.map_err(tag)?;
+
+    let parts = [S::part1, S::part2];
+    let parts = std::array::from_fn(|i| {
+        part.is_none_or(|n| n == i + 1).then(|| {
+            let (answer, elapsed) = time(|| parts[i](&parsed));
+            (answer.map_err(tag), elapsed)
+        })
+    });
+
+    Ok(Run { parse, parts })
+}
+
+fn render<S: Solution>(input: &str) -> Result<Option<Image>, Error> {
+    let parsed = S::parse(input).map_err(|e| e.in_day(S::YEAR, S::DAY))?;
+    S::render(&parsed).map_err(|e| e.in_day(S::YEAR, S::DAY))
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (result, start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::from(42usize), Answer::from(42i64));
+        assert_eq!(Answer::from(-3isize), Answer::Num(-3));
+        assert_eq!(Answer::from("CMZ"), Answer::Text("CMZ".into()));
+
+        for answer in [
+            Answer::Num(-17),
+            Answer::Num(u64::MAX.into()),
+            "abcdffaa".into(),
+        ] {
+            assert_eq!(answer.to_string().parse(), Ok(answer));
+        }
+    }
+
+    mod sums {
+        use crate::{Error, ParseError};
+
+        pub type Input<'a> = Vec<u32>;
+
+        fn parse(input: &str) -> Result<Input<'_>, Error> {
+            input
+                .split(',')
+                .map(|n| {
+                    n.parse()
+                        .map_err(|_| ParseError::at(0, input, n, "expected a number"))
+                })
+                .collect::<Result<_, _>>()
+                .map_err(Error::from)
+        }
+
+        fn sum(nums: &Input) -> u32 {
+            nums.iter().sum()
+        }
+
+        fn product(nums: &Input) -> u32 {
+            nums.iter().product()
+        }
+
+        crate::solve!(2015, 1, "Sums", parse, sum, product);
+    }
+
+    #[test]
+    fn test_run() {
+        let run = (sums::DAY.run)("2,3,4", None).unwrap();
+        let answers: Vec<_> = run
+            .parts
+            .into_iter()
+            .flatten()
+            .map(|(a, _)| a.unwrap())
+            .collect();
+        assert_eq!(answers, [Answer::Num(9), Answer::Num(24)]);
+
+        let run = (sums::DAY.run)("2,3,4", Some(2)).unwrap();
+        assert!(run.parts[0].is_none());
+        assert!(run.parts[1].is_some());
+
+        let Err(Error::Parse(e)) = (sums::DAY.run)("2,x", None) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.puzzle, e.column), (Some((2015, 1)), 3));
+    }
+
+    #[test]
+    fn test_into_answer() {
+        assert_eq!(7u8.into_answer().unwrap(), Answer::Num(7));
+        assert_eq!(Ok::<_, Error>("ab").into_answer().unwrap(), "ab".into());
+        assert!(Err::<usize, _>(Error::unsolvable("no"))
+            .into_answer()
+            .is_err());
+    }
+}
@@ -1,16 +1,19 @@
+use crate::Error;
+
 pub fn p1(input: &str) -> isize {
     input.chars().map(parens_to_value).sum()
 }
 
-pub fn p2(input: &str) -> usize {
+pub fn p2(input: &str) -> Result<usize, Error> {
     let mut floor = 0;
     for (i, n) in input.chars().map(parens_to_value).enumerate() {
         floor += n;
         if floor == -1 {
-            return i + 1;
+            return Ok(i + 1);
         }
     }
-    unreachable!()
+
+    Err(Error::unsolvable("Santa never enters the basement"))
 }
 
 fn parens_to_value(c: char) -> isize {
@@ -40,7 +43,10 @@ mod tests {
     }
     #[test]
     fn test_p2() {
-        assert_eq!(p2(")"), 1);
-        assert_eq!(p2("()())"), 5);
+        assert_eq!(p2(")").unwrap(), 1);
+        assert_eq!(p2("()())").unwrap(), 5);
+        assert!(matches!(p2("(()"), Err(Error::Unsolvable(_))));
     }
 }
+
+crate::solve!(2015, 1, "Not Quite Lisp", p1, p2);
@@ -55,3 +55,5 @@ mod tests {
         assert_eq!(p2("2x3x4\n1x1x10"), 34 + 14);
     }
 }
+
+crate::solve!(2015, 2, "I Was Told There Would Be No Math", p1, p2);
@@ -1,34 +1,41 @@
-use std::collections::HashMap;
+use crate::{
+    grid::{Point, SparseGrid},
+    image::Image,
+    Error,
+};
 
 pub fn p1(input: &str) -> usize {
-    let mut santa = Santa::default();
-    let mut map = HashMap::new();
-
-    for c in input.chars() {
-        santa.go(c, &mut map);
-    }
-
-    map.len()
+    deliver(input, 1).len()
 }
 
 pub fn p2(input: &str) -> usize {
-    let santa = Santa::default();
-    let robot = Santa::default();
-    let mut map = HashMap::new();
+    deliver(input, 2).len()
+}
 
-    let mut santas = [santa, robot];
+/// How many presents each house got, as a heatmap.
+fn render(input: &str) -> Result<Image, Error> {
+    Ok(Image::sparse_heatmap(&deliver(input, 1)))
+}
+
+/// Deliveries by `n` santas taking turns to follow the directions.
+fn deliver(input: &str, n: usize) -> Map {
+    let mut santas: Vec<_> = (0..n).map(|_| Santa::default()).collect();
+    let mut map = Map::new();
+    // every santa starts with the house they stand in front of
+    for santa in &santas {
+        santa.visit(&mut map);
+    }
 
-    let mut i = 0;
-    for c in input.chars() {
-        i = (i + 1) % 2;
-        santas[i].go(c, &mut map);
+    for (i, c) in input.chars().enumerate() {
+        santas[i % n].go(c, &mut map);
     }
 
-    map.len()
+    map
 }
 
-type Pos = (isize, isize);
-type Map = HashMap<Pos, usize>;
+type Pos = Point;
+/// How many presents each house got.
+type Map = SparseGrid<u32>;
 
 #[derive(Default)]
 struct Santa {
@@ -37,15 +44,12 @@ struct Santa {
 
 impl Santa {
     fn go(&mut self, c: char, map: &mut Map) {
-        let next_pos = self.next_pos(c);
-
-        self.visit(map);
-        self.pos = next_pos;
+        self.pos = self.next_pos(c);
         self.visit(map);
     }
 
     fn visit(&self, map: &mut Map) {
-        map.entry(self.pos).and_modify(|p| *p += 1).or_insert(1);
+        *map.entry(self.pos).or_insert(0) += 1;
     }
 
     fn offset(c: char) -> Pos {
@@ -82,4 +86,24 @@ mod tests {
         assert_eq!(p2("^>v<"), 3);
         assert_eq!(p2("^v^v^v^v^v"), 11);
     }
+
+    #[test]
+    fn test_render() {
+        let pixels = render("^v>").unwrap().pixels().clone();
+        assert_eq!((pixels.width(), pixels.height()), (2, 2));
+        // the starting house got a present before and after going up
+        assert_eq!(pixels[(0, 1)], [255; 3]);
+        assert_eq!(pixels[(0, 0)], pixels[(1, 1)]);
+        assert_ne!(pixels[(0, 0)], [0; 3]);
+        assert_eq!(pixels[(1, 0)], [0; 3]);
+    }
 }
+
+crate::solve!(
+    2015,
+    3,
+    "Perfectly Spherical Houses in a Vacuum",
+    p1,
+    p2,
+    render = render
+);
@@ -1,45 +1,105 @@
-use md5;
-
-pub fn p1(input: &str) -> usize {
-    let mut i = 1;
-    loop {
-        let hex = hex_digest(&format!("{input}{i}"));
-        if hex.starts_with("00000") {
-            return i as usize;
-        }
-        i += 1;
-    }
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use md5::{Context, Digest};
+
+use crate::Error;
+
+/// Nonces are searched up to here before giving up.
+const MAX_NONCE: usize = u32::MAX as usize;
+
+/// How many nonces a thread claims at a time.
+const BATCH: usize = 4096;
+
+pub fn p1(input: &str) -> Result<usize, Error> {
+    mine(input, 5)
+}
+
+pub fn p2(input: &str) -> Result<usize, Error> {
+    mine(input, 6)
 }
 
-pub fn p2(input: &str) -> usize {
-    let mut i = 1;
-    loop {
-        let hex = hex_digest(&format!("{input}{i}"));
-        if hex.starts_with("000000") {
-            return i as usize;
+/// The smallest nonce whose hash with `input` starts with `zeros` zero hex
+/// digits. Threads claim batches of nonces in increasing order and stop once
+/// a nonce below their next batch was found, so every smaller nonce has been
+/// checked whatever the scheduling.
+fn mine(input: &str, zeros: usize) -> Result<usize, Error> {
+    let mut key = Context::new();
+    key.consume(input);
+
+    let next = AtomicUsize::new(1);
+    let found = AtomicUsize::new(usize::MAX);
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let mut nonce = String::new();
+                loop {
+                    let start = next.fetch_add(BATCH, Ordering::Relaxed);
+                    if start > MAX_NONCE || start > found.load(Ordering::Relaxed) {
+                        break;
+                    }
+
+                    let batch = start..(start + BATCH).min(MAX_NONCE + 1);
+                    let hit = batch.into_iter().find(|i| {
+                        nonce.clear();
+                        write!(nonce, "{i}").unwrap();
+                        let mut hash = key.clone();
+                        hash.consume(&nonce);
+                        has_zeros(&hash.compute(), zeros)
+                    });
+                    if let Some(i) = hit {
+                        found.fetch_min(i, Ordering::Relaxed);
+                    }
+                }
+            });
         }
-        i += 1;
+    });
+
+    match found.into_inner() {
+        usize::MAX => Err(Error::unsolvable(format!(
+            "no nonce up to {MAX_NONCE} gives {zeros} zeros"
+        ))),
+        nonce => Ok(nonce),
     }
 }
 
-fn hex_digest(s: &str) -> String {
-    let digest = md5::compute(s);
-    format!("{digest:x}")
+/// Whether the hex form of `digest` starts with `zeros` zeros.
+fn has_zeros(digest: &Digest, zeros: usize) -> bool {
+    let (bytes, nibble) = (zeros / 2, zeros % 2 == 1);
+    digest.0[..bytes].iter().all(|&b| b == 0) && (!nibble || digest.0[bytes] >> 4 == 0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mine() {
+        assert!(has_zeros(&md5::compute("abcdef609043"), 5));
+        assert!(!has_zeros(&md5::compute("abcdef609043"), 6));
+
+        let expected = (1..)
+            .find(|i| format!("{:x}", md5::compute(format!("abcdef{i}"))).starts_with("000"))
+            .unwrap();
+        assert_eq!(mine("abcdef", 3).unwrap(), expected);
+    }
+
     #[ignore]
     #[test]
     fn test_p1() {
-        assert_eq!(p1("abcdef"), 609043);
+        assert_eq!(p1("abcdef").unwrap(), 609043);
     }
 
     #[ignore]
     #[test]
     fn test_p2() {
-        assert_eq!(p2("abcdef"), 6742839);
+        assert_eq!(p2("abcdef").unwrap(), 6742839);
     }
 }
+
+crate::solve!(2015, 4, "The Ideal Stocking Stuffer", p1, p2);
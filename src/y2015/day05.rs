@@ -79,3 +79,5 @@ mod tests {
         );
     }
 }
+
+crate::solve!(2015, 5, "Doesn't He Have Intern-Elves For This?", p1, p2);
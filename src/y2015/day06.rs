@@ -1,5 +1,7 @@
 use std::{iter::Skip, str::Chars};
 
+use crate::{grid::Grid, image::Image, Error};
+
 pub fn p1(input: &str) -> usize {
     exec(input, Mode::P1)
 }
@@ -9,14 +11,37 @@ pub fn p2(input: &str) -> usize {
 }
 
 fn exec(input: &str, mode: Mode) -> usize {
-    let mut grid = Grid::new(mode);
+    follow(input, mode).count_lights()
+}
+
+/// How many lights wide and tall Santa's grid is.
+const LIGHTS: usize = 1000;
 
-    for line in input.lines() {
-        let op = Op::parse(line);
-        grid.exec(op);
+/// The brightness of every light, as a greyscale picture with a pixel per
+/// light. Lights off Santa's grid are refused rather than drawn.
+fn render(input: &str) -> Result<Image, Error> {
+    let lights = follow(input, Mode::P2);
+    if lights
+        .xs
+        .last()
+        .max(lights.ys.last())
+        .is_some_and(|&end| end > LIGHTS)
+    {
+        return Err(format!("can't render lights beyond {LIGHTS}x{LIGHTS}").into());
     }
 
-    grid.count_lights()
+    Ok(Image::greyscale(&lights.brightness()))
+}
+
+fn follow(input: &str, mode: Mode) -> Lights {
+    let ops: Vec<_> = input.lines().map(Op::parse).collect();
+    let mut lights = Lights::new(&ops, mode);
+
+    for op in &ops {
+        lights.exec(op);
+    }
+
+    lights
 }
 
 const CMD_TURN_ON: &str = "turn on";
@@ -25,14 +50,14 @@ const CMD_TOGGLE: &str = "toggle";
 
 type Pos = (usize, usize);
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum OpType {
     On,
     Off,
     Toggle,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 struct Op {
     op_type: OpType,
     from: Pos,
@@ -62,61 +87,93 @@ impl Op {
             (x1.parse().unwrap(), y1.parse().unwrap()),
         )
     }
-
-    fn exec(&self, n: u8) -> u8 {
-        match self.op_type {
-            OpType::On => 1,
-            OpType::Off => 0,
-            OpType::Toggle => (n + 1) % 2,
-        }
-    }
-
-    fn exec_p2(&self, n: u8) -> u8 {
-        match self.op_type {
-            OpType::On => n + 1,
-            OpType::Off => {
-                if n > 1 {
-                    n - 1
-                } else {
-                    0
-                }
-            }
-            OpType::Toggle => n + 2,
-        }
-    }
 }
 
 enum Mode {
     P1,
     P2,
 }
-struct Grid {
-    lights: Vec<u8>,
+
+impl Mode {
+    fn apply(&self, op_type: &OpType, n: u32) -> u32 {
+        match (self, op_type) {
+            (Mode::P1, OpType::On) => 1,
+            (Mode::P1, OpType::Off) => 0,
+            (Mode::P1, OpType::Toggle) => 1 - n,
+            (Mode::P2, OpType::On) => n + 1,
+            (Mode::P2, OpType::Off) => n.saturating_sub(1),
+            (Mode::P2, OpType::Toggle) => n + 2,
+        }
+    }
+}
+
+/// The lights cut into blocks along every coordinate where an instruction's
+/// rectangle starts or ends. All lights of a block always share the same
+/// state, so only blocks are stored, however large the coordinates.
+struct Lights {
+    xs: Vec<usize>,
+    ys: Vec<usize>,
+    blocks: Grid<u32>,
     mode: Mode,
 }
-impl Grid {
-    fn new(mode: Mode) -> Self {
+
+impl Lights {
+    fn new(ops: &[Op], mode: Mode) -> Self {
+        let bounds = |axis: fn(Pos) -> usize| {
+            let mut bounds: Vec<_> = ops
+                .iter()
+                .flat_map(|op| [axis(op.from), axis(op.to) + 1])
+                .collect();
+            bounds.sort_unstable();
+            bounds.dedup();
+            bounds
+        };
+        let (xs, ys) = (bounds(|(x, _)| x), bounds(|(_, y)| y));
+        let blocks = Grid::new(xs.len().saturating_sub(1), ys.len().saturating_sub(1), 0);
+
         Self {
-            lights: vec![0; 1_000_000],
+            xs,
+            ys,
+            blocks,
             mode,
         }
     }
 
     fn count_lights(&self) -> usize {
-        self.lights.iter().fold(0, |acc, n| acc + *n as usize)
+        self.blocks
+            .iter()
+            .map(|((i, j), &n)| {
+                let (width, height) = (self.xs[i + 1] - self.xs[i], self.ys[j + 1] - self.ys[j]);
+                n as usize * width * height
+            })
+            .sum()
+    }
+
+    /// Every light of Santa's grid on its own.
+    fn brightness(&self) -> Grid<u32> {
+        let mut grid = Grid::new(LIGHTS, LIGHTS, 0);
+
+        for ((i, j), &n) in self.blocks.iter() {
+            for x in self.xs[i]..self.xs[i + 1] {
+                for y in self.ys[j]..self.ys[j + 1] {
+                    grid[(x, y)] = n;
+                }
+            }
+        }
+
+        grid
     }
 
-    fn exec(&mut self, op: Op) {
-        let (x0, y0) = op.from;
-        let (x1, y1) = op.to;
+    fn exec(&mut self, op: &Op) {
+        // `op` starts and ends on block boundaries by construction
+        let block = |bounds: &[usize], n: usize| bounds.partition_point(|&b| b < n);
+        let (i0, i1) = (block(&self.xs, op.from.0), block(&self.xs, op.to.0 + 1));
+        let (j0, j1) = (block(&self.ys, op.from.1), block(&self.ys, op.to.1 + 1));
 
-        for x in x0..=x1 {
-            for y in y0..=y1 {
-                let light = &mut self.lights[x + y * 1000];
-                *light = match self.mode {
-                    Mode::P1 => op.exec(*light),
-                    Mode::P2 => op.exec_p2(*light),
-                };
+        for i in i0..i1 {
+            for j in j0..j1 {
+                let light = &mut self.blocks[(i, j)];
+                *light = self.mode.apply(&op.op_type, *light);
             }
         }
     }
@@ -139,6 +196,8 @@ fn skip_cmd(s: &str) -> (OpType, Skip<Chars<'_>>) {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -170,5 +229,75 @@ mod tests {
     fn test_p2() {
         assert_eq!(p2("turn on 0,0 through 0,0"), 1);
         assert_eq!(p2("toggle 0,0 through 999,999"), 2_000_000);
+        assert_eq!(
+            p2("toggle 0,0 through 999999999,999999999\n\
+            turn off 5,5 through 999999999,999999999"),
+            2 * 1_000_000_000 * 1_000_000_000 - 999999995 * 999999995
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let image = render("turn on 1,0 through 2,1\ntoggle 2,1 through 2,1").unwrap();
+        let pixels = image.pixels();
+        assert_eq!((pixels.width(), pixels.height()), (LIGHTS, LIGHTS));
+        assert_eq!(pixels.row(1)[..4], [[0; 3], [85; 3], [255; 3], [0; 3]]);
+
+        assert!(render("turn on 0,0 through 999999999,1").is_err());
+    }
+
+    /// The original light by light implementation, as an oracle.
+    fn brute_force(ops: &[Op], mode: &Mode) -> usize {
+        let mut grid = Grid::new(40, 40, 0);
+        for op in ops {
+            for x in op.from.0..=op.to.0 {
+                for y in op.from.1..=op.to.1 {
+                    let light = &mut grid[(x, y)];
+                    *light = match mode {
+                        Mode::P1 => exec_p1(op, *light),
+                        Mode::P2 => exec_p2(op, *light),
+                    };
+                }
+            }
+        }
+        grid.cells().iter().fold(0, |acc, n| acc + *n as usize)
+    }
+
+    fn exec_p1(op: &Op, n: u8) -> u8 {
+        match op.op_type {
+            OpType::On => 1,
+            OpType::Off => 0,
+            OpType::Toggle => (n + 1) % 2,
+        }
+    }
+
+    fn exec_p2(op: &Op, n: u8) -> u8 {
+        match op.op_type {
+            OpType::On => n + 1,
+            OpType::Off => n.saturating_sub(1),
+            OpType::Toggle => n + 2,
+        }
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let op_type = prop_oneof![Just(OpType::On), Just(OpType::Off), Just(OpType::Toggle)];
+        let span = || (0..40usize, 0..40usize).prop_map(|(a, b)| (a.min(b), a.max(b)));
+        (op_type, span(), span())
+            .prop_map(|(op_type, (x0, x1), (y0, y1))| Op::new(op_type, (x0, y0), (x1, y1)))
+    }
+
+    proptest! {
+        #[test]
+        fn test_against_brute_force(ops in prop::collection::vec(op(), 0..30)) {
+            for mode in [Mode::P1, Mode::P2] {
+                let mut lights = Lights::new(&ops, mode);
+                for op in &ops {
+                    lights.exec(op);
+                }
+                prop_assert_eq!(lights.count_lights(), brute_force(&ops, &lights.mode));
+            }
+        }
     }
 }
+
+crate::solve!(2015, 6, "Probably a Fire Hazard", p1, p2, render = render);
@@ -0,0 +1,267 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use super::{Emu, Op, Wire};
+
+impl Emu {
+    /// The circuit as a Graphviz digraph, with a node per gate and an edge
+    /// per wire, labelled with its signal once resolved.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph circuit {\n    node [shape=box];\n");
+        let read = self.read_wires();
+
+        for (dst, op) in self.gates() {
+            writeln!(dot, "    {:?} [label={:?}];", gate(dst), op.kind()).unwrap();
+
+            for (i, input) in op.operands().enumerate() {
+                match input {
+                    Wire::Lit(n) => {
+                        let constant = format!("{dst}:{i}");
+                        writeln!(dot, "    {constant:?} [label=\"{n}\", shape=plaintext];")
+                            .unwrap();
+                        writeln!(dot, "    {constant:?} -> {:?};", gate(dst)).unwrap();
+                    }
+                    Wire::Str(src) => {
+                        let from = if self.logic.contains_key(src) {
+                            gate(src)
+                        } else {
+                            // an undefined wire comes from outside the circuit
+                            writeln!(dot, "    {src:?} [shape=plaintext];").unwrap();
+                            src.clone()
+                        };
+                        let label = self.wire_label(src);
+                        writeln!(dot, "    {from:?} -> {:?} [label={label:?}];", gate(dst))
+                            .unwrap();
+                    }
+                }
+            }
+
+            if !read.contains(dst.as_str()) {
+                let output = format!("{dst}:out");
+                writeln!(dot, "    {output:?} [label=\"\", shape=point];").unwrap();
+                let label = self.wire_label(dst);
+                writeln!(dot, "    {:?} -> {output:?} [label={label:?}];", gate(dst)).unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// The circuit as a structural Verilog module: a gate array per logic
+    /// gate, plain wiring for shifts, rotations and constants, and continuous
+    /// assignments for arithmetic. Undefined wires become
+    /// inputs and wires nobody reads become outputs. Every wire is prefixed
+    /// with `w_` to keep clear of Verilog keywords.
+    pub fn to_verilog(&self, module: &str) -> String {
+        let read = self.read_wires();
+        let undefined: BTreeSet<_> = read
+            .iter()
+            .filter(|w| !self.logic.contains_key(**w))
+            .collect();
+        let outputs: Vec<_> = self
+            .gates()
+            .map(|(dst, _)| dst)
+            .filter(|dst| !read.contains(dst.as_str()))
+            .collect();
+        let bits = self.width.bits();
+        let bus = format!("[{}:0]", bits - 1);
+
+        let ports: Vec<_> = undefined
+            .iter()
+            .map(|w| format!("    input wire {bus} w_{w}"))
+            .chain(
+                outputs
+                    .iter()
+                    .map(|w| format!("    output wire {bus} w_{w}")),
+            )
+            .collect();
+        let mut v = format!("module {module} (\n{}\n);\n", ports.join(",\n"));
+
+        for (dst, _) in self.gates().filter(|(dst, _)| read.contains(dst.as_str())) {
+            writeln!(v, "    wire {bus} w_{dst};").unwrap();
+        }
+
+        for (dst, op) in self.gates() {
+            let out = format!("w_{dst}");
+            let inputs: Vec<_> = op.operands().map(|w| verilog_operand(w, bits)).collect();
+            let gate = match op {
+                Op::Id(Wire::Lit(_)) => format!("assign {out} = {}", inputs[0]),
+                Op::Id(_) => format!("buf g_{dst} {bus} ({out}, {})", inputs[0]),
+                Op::Not(_) => format!("not g_{dst} {bus} ({out}, {})", inputs[0]),
+                Op::And(..) | Op::Or(..) | Op::Xor(..) | Op::Nand(..) | Op::Nor(..) => {
+                    let kind = op.kind().to_lowercase();
+                    format!("{kind} g_{dst} {bus} ({out}, {})", inputs.join(", "))
+                }
+                Op::Add(..) => format!("assign {out} = {} + {}", inputs[0], inputs[1]),
+                Op::Sub(..) => format!("assign {out} = {} - {}", inputs[0], inputs[1]),
+                Op::Lshift(src, n) => {
+                    format!("assign {out} = {}", shifted(src, *n, bits, true))
+                }
+                Op::Rshift(src, n) => {
+                    format!("assign {out} = {}", shifted(src, *n, bits, false))
+                }
+                Op::Lrotate(src, n) => {
+                    format!("assign {out} = {}", rotated(src, *n, bits, true))
+                }
+                Op::Rrotate(src, n) => {
+                    format!("assign {out} = {}", rotated(src, *n, bits, false))
+                }
+            };
+            writeln!(v, "    {gate};").unwrap();
+        }
+
+        v.push_str("endmodule\n");
+        v
+    }
+
+    /// The gates by output wire, in order.
+    fn gates(&self) -> impl Iterator<Item = (&String, &Op)> {
+        let mut gates: Vec<_> = self.logic.iter().collect();
+        gates.sort_by_key(|&(dst, _)| dst);
+        gates.into_iter()
+    }
+
+    /// The wires some gate reads.
+    fn read_wires(&self) -> BTreeSet<&str> {
+        self.logic.values().flat_map(Op::inputs).collect()
+    }
+
+    fn wire_label(&self, wire: &str) -> String {
+        match self.state.get(wire) {
+            Some(n) => format!("{wire} = {n}"),
+            None => wire.into(),
+        }
+    }
+}
+
+impl Op {
+    fn kind(&self) -> String {
+        match self {
+            Op::Id(_) => "ID".into(),
+            Op::Not(_) => "NOT".into(),
+            Op::And(..) => "AND".into(),
+            Op::Or(..) => "OR".into(),
+            Op::Xor(..) => "XOR".into(),
+            Op::Nand(..) => "NAND".into(),
+            Op::Nor(..) => "NOR".into(),
+            Op::Add(..) => "ADD".into(),
+            Op::Sub(..) => "SUB".into(),
+            Op::Lshift(_, n) => format!("LSHIFT {n}"),
+            Op::Rshift(_, n) => format!("RSHIFT {n}"),
+            Op::Lrotate(_, n) => format!("LROTATE {n}"),
+            Op::Rrotate(_, n) => format!("RROTATE {n}"),
+        }
+    }
+}
+
+fn gate(wire: &str) -> String {
+    format!("{wire}:gate")
+}
+
+fn verilog_operand(wire: &Wire, bits: u32) -> String {
+    match wire {
+        Wire::Lit(n) => format!("{bits}'d{n}"),
+        Wire::Str(s) => format!("w_{s}"),
+    }
+}
+
+/// `src` shifted by `n` bits on a `bits` wide bus, as wiring. Literals can't
+/// be sliced, so they're shifted with operators instead.
+fn shifted(src: &Wire, n: u16, bits: u32, left: bool) -> String {
+    let (operand, n) = (verilog_operand(src, bits), u32::from(n));
+    match (src, n) {
+        (_, 0) => operand,
+        (_, n) if n >= bits => format!("{bits}'d0"),
+        (Wire::Lit(_), n) => format!("{operand} {} {n}", if left { "<<" } else { ">>" }),
+        (_, n) if left => format!("{{{operand}[{}:0], {n}'b0}}", bits - 1 - n),
+        (_, n) => format!("{{{n}'b0, {operand}[{}:{n}]}}", bits - 1),
+    }
+}
+
+/// `src` rotated by `n` bits on a `bits` wide bus, as wiring, or with
+/// operators for literals like `shifted`.
+fn rotated(src: &Wire, n: u16, bits: u32, left: bool) -> String {
+    let (operand, n) = (verilog_operand(src, bits), u32::from(n) % bits);
+    // rotating left by `n` bits is rotating right by `bits - n`
+    let n = if left || n == 0 { n } else { bits - n };
+    match (src, n) {
+        (_, 0) => operand,
+        (Wire::Lit(_), n) => format!("{operand} << {n} | {operand} >> {}", bits - n),
+        (_, n) => format!(
+            "{{{operand}[{}:0], {operand}[{}:{}]}}",
+            bits - 1 - n,
+            bits - 1,
+            bits - n
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_circuit, Emu, Width};
+
+    const INPUT: &str = "123 -> x\nx AND y -> d\nd LSHIFT 2 -> a\nNOT d -> e\n1 OR e -> f";
+
+    #[test]
+    fn test_dot() {
+        let mut emu = parse_circuit(INPUT).unwrap();
+        emu.wire(super::Op::Id(456.into()), "y".into());
+        emu.resolve("d").unwrap();
+
+        let dot = emu.to_dot();
+        assert!(dot.starts_with("digraph circuit {\n"));
+        assert!(dot.contains("    \"d:gate\" [label=\"AND\"];\n"));
+        assert!(dot.contains("    \"x:gate\" -> \"d:gate\" [label=\"x = 123\"];\n"));
+        assert!(dot.contains("    \"d:gate\" -> \"a:gate\" [label=\"d = 72\"];\n"));
+        assert!(dot.contains("    \"f:0\" -> \"f:gate\";\n"));
+        assert!(dot.contains("    \"a:gate\" -> \"a:out\" [label=\"a\"];\n"));
+    }
+
+    #[test]
+    fn test_verilog() {
+        let emu = parse_circuit(INPUT).unwrap();
+
+        assert_eq!(
+            emu.to_verilog("day07"),
+            "module day07 (
+    input wire [15:0] w_y,
+    output wire [15:0] w_a,
+    output wire [15:0] w_f
+);
+    wire [15:0] w_d;
+    wire [15:0] w_e;
+    wire [15:0] w_x;
+    assign w_a = {w_d[13:0], 2'b0};
+    and g_d [15:0] (w_d, w_x, w_y);
+    not g_e [15:0] (w_e, w_d);
+    or g_f [15:0] (w_f, 16'd1, w_e);
+    assign w_x = 16'd123;
+endmodule
+"
+        );
+    }
+
+    #[test]
+    fn test_verilog_width() {
+        let input = "x XOR y -> a\nx SUB 1 -> b\nx RROTATE 3 -> c\nx LROTATE 8 -> d";
+        let emu = Emu::parse(input, Width::W8).unwrap();
+
+        let v = emu.to_verilog("day07");
+        assert!(v.contains("    input wire [7:0] w_x,\n"));
+        assert!(v.contains("    xor g_a [7:0] (w_a, w_x, w_y);\n"));
+        assert!(v.contains("    assign w_b = w_x - 8'd1;\n"));
+        assert!(v.contains("    assign w_c = {w_x[2:0], w_x[7:3]};\n"));
+        assert!(v.contains("    assign w_d = w_x;\n"));
+    }
+
+    #[test]
+    fn test_verilog_literals() {
+        let input = "5 LSHIFT 2 -> a\n5 RSHIFT 1 -> b\n5 LROTATE 3 -> c\n5 RSHIFT 0 -> d";
+        let v = parse_circuit(input).unwrap().to_verilog("day07");
+
+        assert!(v.contains("    assign w_a = 16'd5 << 2;\n"));
+        assert!(v.contains("    assign w_b = 16'd5 >> 1;\n"));
+        assert!(v.contains("    assign w_c = 16'd5 << 3 | 16'd5 >> 13;\n"));
+        assert!(v.contains("    assign w_d = 16'd5;\n"));
+    }
+}
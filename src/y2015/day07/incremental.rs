@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use super::{Emu, Op};
+use crate::graph::{topo_sort, Graph};
+
+impl Emu {
+    /// Drives `wire` with `signal` instead of its gate, forgetting only the
+    /// signals that depend on it.
+    pub fn override_wire(&mut self, wire: &str, signal: u64) {
+        self.wire(Op::Id(signal.into()), wire.into());
+        self.invalidate(wire);
+    }
+
+    /// The wires whose signal would change, with their new signal, were
+    /// `wire` driven with `signal`. Only the resolved wires downstream of
+    /// `wire` are looked at, and the circuit is left as it is. Like a
+    /// literal, `signal` is cut down to the width of the wires.
+    pub fn what_if(&self, wire: &str, signal: u64) -> BTreeMap<String, u64> {
+        let signal = signal & self.width.mask();
+        let mut changed = BTreeMap::new();
+        if self.state.get(wire) == Some(&signal) {
+            return changed;
+        }
+        changed.insert(wire.to_string(), signal);
+
+        let order = topo_sort(&Resolved(self), [wire.to_string()])
+            .expect("resolved wires don't form cycles");
+        for w in order.into_iter().skip(1) {
+            let op = &self.logic[&w];
+            if !op.inputs().any(|input| changed.contains_key(input)) {
+                continue;
+            }
+
+            let n = op.eval(self.width, |input| {
+                changed.get(input).copied().unwrap_or(self.state[input])
+            });
+            if self.state[&w] != n {
+                changed.insert(w, n);
+            }
+        }
+
+        changed
+    }
+
+    /// Forgets the signal on `wire` and on every wire depending on it.
+    fn invalidate(&mut self, wire: &str) {
+        let mut stack = vec![wire.to_string()];
+
+        while let Some(w) = stack.pop() {
+            // the readers of a wire that isn't resolved can't be either
+            if self.state.remove(&w).is_some() || w == wire {
+                stack.extend(self.readers.get(&w).into_iter().flatten().cloned());
+            }
+        }
+    }
+}
+
+/// The resolved wires, as a graph from each wire to the gates reading it.
+struct Resolved<'a>(&'a Emu);
+
+impl Graph for Resolved<'_> {
+    type Node = String;
+    type Cost = usize;
+
+    fn neighbours(&self, wire: &String) -> impl Iterator<Item = (String, usize)> {
+        let Resolved(emu) = self;
+        emu.readers
+            .get(wire)
+            .into_iter()
+            .flatten()
+            .filter(|reader| emu.state.contains_key(*reader))
+            .map(|reader| (reader.clone(), 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_circuit;
+    use super::*;
+
+    const INPUT: &str =
+        "123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nNOT d -> h\ne RSHIFT 9 -> g";
+
+    #[test]
+    fn test_override_wire() {
+        let mut emu = parse_circuit(INPUT).unwrap();
+        for w in ["h", "g"] {
+            emu.resolve(w).unwrap();
+        }
+
+        emu.override_wire("y", 0);
+        assert_eq!(emu.state.keys().collect::<Vec<_>>(), ["x"]);
+        assert_eq!(emu.resolve("h").unwrap(), 0xffff);
+        assert_eq!(emu.resolve("e").unwrap(), 123);
+    }
+
+    #[test]
+    fn test_what_if() {
+        let mut emu = parse_circuit(INPUT).unwrap();
+        for w in ["h", "g"] {
+            emu.resolve(w).unwrap();
+        }
+
+        // x = 123 has bit 0 set already, so e = x | y and g = e >> 9 stay put
+        assert_eq!(
+            emu.what_if("y", 457),
+            BTreeMap::from([
+                ("d".into(), 73),
+                ("h".into(), 0xffff ^ 73),
+                ("y".into(), 457)
+            ])
+        );
+        assert_eq!(emu.what_if("y", 456), BTreeMap::new());
+        // only the low 16 bits reach the wire
+        assert_eq!(emu.what_if("y", 0x1_0000 | 457), emu.what_if("y", 457));
+        assert_eq!(emu.what_if("y", 0x1_0000 | 456), BTreeMap::new());
+        assert_eq!(emu.resolve("d").unwrap(), 72);
+
+        let changed = emu.what_if("x", 1023);
+        emu.override_wire("x", 1023);
+        for (w, n) in changed {
+            assert_eq!(emu.resolve(&w).unwrap(), n, "{w}");
+        }
+        assert_eq!(emu.resolve("g").unwrap(), 1);
+    }
+}
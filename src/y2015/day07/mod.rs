@@ -0,0 +1,555 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_until},
+    character::complete::alpha1,
+    combinator::{all_consuming, map},
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+
+mod export;
+mod incremental;
+mod reverse;
+
+pub use reverse::{Assignment, Solutions};
+
+use crate::{
+    graph::{topo_sort, Graph},
+    parse::{lines, uint},
+    Error,
+};
+
+pub type Input<'a> = Emu;
+
+pub fn p1(emu: &Emu) -> Result<usize, Error> {
+    let mut emu = emu.clone();
+
+    Ok(emu.resolve("a")? as usize)
+}
+
+pub fn p2(emu: &Emu) -> Result<usize, Error> {
+    let mut emu = emu.clone();
+
+    let a = emu.resolve("a")?;
+    emu.override_wire("b", a);
+
+    Ok(emu.resolve("a")? as usize)
+}
+
+pub fn parse_circuit(input: &str) -> Result<Emu, Error> {
+    Emu::parse(input, Width::W16)
+}
+
+/// How many bits each wire carries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Width {
+    W8,
+    #[default]
+    W16,
+    W32,
+    W64,
+}
+
+impl Width {
+    pub fn bits(self) -> u32 {
+        match self {
+            Width::W8 => 8,
+            Width::W16 => 16,
+            Width::W32 => 32,
+            Width::W64 => 64,
+        }
+    }
+
+    /// The largest signal a wire can carry.
+    pub fn mask(self) -> u64 {
+        u64::MAX >> (64 - self.bits())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Emu {
+    width: Width,
+    logic: HashMap<String, Op>,
+    /// The gates reading each wire, the reverse of `logic`.
+    readers: HashMap<String, HashSet<String>>,
+    state: HashMap<String, u64>,
+}
+
+impl Emu {
+    pub fn new(width: Width) -> Self {
+        Self {
+            width,
+            ..Self::default()
+        }
+    }
+
+    /// Parses a circuit of `width` bits wires, one gate per line.
+    pub fn parse(input: &str, width: Width) -> Result<Self, Error> {
+        let mut emu = Self::new(width);
+
+        for line in lines(input).filter(|l| !l.text.is_empty()) {
+            let (op, dst) = line.parse(Op::parse)?;
+            let too_wide = op.operands().find_map(|w| match w {
+                Wire::Lit(n) if *n > width.mask() => Some(n),
+                _ => None,
+            });
+            if let Some(n) = too_wide {
+                // a literal operand is a whole token, and it comes before any
+                // shift amount that could be spelled the same
+                let operand = line
+                    .text
+                    .split(' ')
+                    .find(|t| t.parse::<u64>() == Ok(*n))
+                    .unwrap_or(line.text);
+                let reason = format!("{n} doesn't fit in {} bits", width.bits());
+                return Err(line.error(operand, reason).into());
+            }
+            emu.wire(op, dst);
+        }
+
+        Ok(emu)
+    }
+
+    pub fn width(&self) -> Width {
+        self.width
+    }
+
+    /// Drives `dst` with `op`, replacing whatever drove it before.
+    fn wire(&mut self, op: Op, dst: String) {
+        if let Some(old) = self.logic.remove(&dst) {
+            for input in old.inputs() {
+                if let Some(readers) = self.readers.get_mut(input) {
+                    readers.remove(&dst);
+                }
+            }
+        }
+
+        for input in op.inputs() {
+            self.readers
+                .entry(input.into())
+                .or_default()
+                .insert(dst.clone());
+        }
+        self.logic.insert(dst, op);
+    }
+
+    /// The signal on `wire`, evaluating the wires it depends on first.
+    pub fn resolve(&mut self, wire: &str) -> Result<u64, Error> {
+        if let Some(&n) = self.state.get(wire) {
+            return Ok(n);
+        }
+
+        for w in self.order(wire)?.into_iter().rev() {
+            if !self.state.contains_key(&w) {
+                let n = self.logic[&w].eval(self.width, |input| self.state[input]);
+                self.state.insert(w, n);
+            }
+        }
+
+        Ok(self.state[wire])
+    }
+
+    /// `wire` and the wires it depends on, each before the wires it reads.
+    fn order(&self, wire: &str) -> Result<Vec<String>, Error> {
+        let order = topo_sort(self, [wire.to_string()]).map_err(|w| {
+            Error::unsolvable(format!(
+                "wires {} form a cycle",
+                self.cycle(&w).join(" -> ")
+            ))
+        })?;
+
+        if let Some(undefined) = order.iter().find(|w| !self.logic.contains_key(*w)) {
+            let reader = order.iter().find(|r| {
+                self.logic
+                    .get(*r)
+                    .is_some_and(|op| op.inputs().any(|i| i == undefined))
+            });
+            return Err(Error::unsolvable(match reader {
+                Some(reader) => format!("wire {undefined} read by {reader} is undefined"),
+                None => format!("wire {undefined} is undefined"),
+            }));
+        }
+
+        Ok(order)
+    }
+
+    /// The wires of a cycle through `wire`, from `wire` back to it, each
+    /// reading the next.
+    fn cycle(&self, wire: &str) -> Vec<String> {
+        let mut readers: HashMap<String, String> = HashMap::new();
+        let mut queue = VecDeque::from([wire.to_string()]);
+
+        while let Some(w) = queue.pop_front() {
+            for (input, _) in self.neighbours(&w) {
+                if input == wire {
+                    let mut cycle = vec![input, w.clone()];
+                    while let Some(reader) = readers.get(cycle.last().unwrap()) {
+                        cycle.push(reader.clone());
+                    }
+                    cycle.reverse();
+                    return cycle;
+                }
+                if !readers.contains_key(&input) {
+                    readers.insert(input.clone(), w.clone());
+                    queue.push_back(input);
+                }
+            }
+        }
+
+        vec![wire.to_string()]
+    }
+}
+
+/// The circuit as a graph from each wire to the wires its gate reads.
+impl Graph for Emu {
+    type Node = String;
+    type Cost = usize;
+
+    fn neighbours(&self, wire: &String) -> impl Iterator<Item = (String, usize)> {
+        self.logic
+            .get(wire)
+            .into_iter()
+            .flat_map(Op::inputs)
+            .map(|input| (input.to_string(), 1))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Wire {
+    Str(String),
+    Lit(u64),
+}
+
+impl From<&str> for Wire {
+    fn from(s: &str) -> Self {
+        Self::Str(s.into())
+    }
+}
+
+impl From<u64> for Wire {
+    fn from(n: u64) -> Self {
+        Self::Lit(n)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Op {
+    Id(Wire),
+    Not(Wire),
+    And(Wire, Wire),
+    Or(Wire, Wire),
+    Xor(Wire, Wire),
+    Nand(Wire, Wire),
+    Nor(Wire, Wire),
+    /// Wrapping addition.
+    Add(Wire, Wire),
+    /// Wrapping subtraction.
+    Sub(Wire, Wire),
+    Lshift(Wire, u16),
+    Rshift(Wire, u16),
+    Lrotate(Wire, u16),
+    Rrotate(Wire, u16),
+}
+
+impl Op {
+    /// The wires and literals the gate reads, shift amounts aside.
+    fn operands(&self) -> impl Iterator<Item = &Wire> {
+        let operands = match self {
+            Op::Id(a)
+            | Op::Not(a)
+            | Op::Lshift(a, _)
+            | Op::Rshift(a, _)
+            | Op::Lrotate(a, _)
+            | Op::Rrotate(a, _) => [Some(a), None],
+            Op::And(a, b)
+            | Op::Or(a, b)
+            | Op::Xor(a, b)
+            | Op::Nand(a, b)
+            | Op::Nor(a, b)
+            | Op::Add(a, b)
+            | Op::Sub(a, b) => [Some(a), Some(b)],
+        };
+        operands.into_iter().flatten()
+    }
+
+    /// The wires the gate reads, literals aside.
+    fn inputs(&self) -> impl Iterator<Item = &str> {
+        self.operands().filter_map(|w| match w {
+            Wire::Str(s) => Some(s.as_str()),
+            Wire::Lit(_) => None,
+        })
+    }
+
+    /// The output of the gate on `width` bits wires, given the signal on each
+    /// of its input wires.
+    fn eval(&self, width: Width, signal: impl Fn(&str) -> u64) -> u64 {
+        let operand = |w: &Wire| match w {
+            Wire::Lit(n) => *n & width.mask(),
+            Wire::Str(s) => signal(s),
+        };
+        let bits = width.bits();
+
+        let n = match self {
+            Op::Id(src) => operand(src),
+            Op::Not(src) => !operand(src),
+            Op::And(a, b) => operand(a) & operand(b),
+            Op::Or(a, b) => operand(a) | operand(b),
+            Op::Xor(a, b) => operand(a) ^ operand(b),
+            Op::Nand(a, b) => !(operand(a) & operand(b)),
+            Op::Nor(a, b) => !(operand(a) | operand(b)),
+            Op::Add(a, b) => operand(a).wrapping_add(operand(b)),
+            Op::Sub(a, b) => operand(a).wrapping_sub(operand(b)),
+            Op::Lshift(src, by) => operand(src).checked_shl((*by).into()).unwrap_or(0),
+            Op::Rshift(src, by) => operand(src).checked_shr((*by).into()).unwrap_or(0),
+            Op::Lrotate(src, by) => rotate_left(operand(src), (*by).into(), bits),
+            Op::Rrotate(src, by) => rotate_left(operand(src), bits - u32::from(*by) % bits, bits),
+        };
+
+        n & width.mask()
+    }
+
+    fn parse(input: &str) -> IResult<&str, (Op, String)> {
+        let op_parsers = alt((
+            all_consuming(map(parse_wire, Op::Id)),
+            map(preceded(tag("NOT "), parse_wire), Op::Not),
+            parse_binop(parse_wire, " AND ", parse_wire, Op::And),
+            parse_binop(parse_wire, " OR ", parse_wire, Op::Or),
+            parse_binop(parse_wire, " XOR ", parse_wire, Op::Xor),
+            parse_binop(parse_wire, " NAND ", parse_wire, Op::Nand),
+            parse_binop(parse_wire, " NOR ", parse_wire, Op::Nor),
+            parse_binop(parse_wire, " ADD ", parse_wire, Op::Add),
+            parse_binop(parse_wire, " SUB ", parse_wire, Op::Sub),
+            parse_binop(parse_wire, " LSHIFT ", uint, Op::Lshift),
+            parse_binop(parse_wire, " RSHIFT ", uint, Op::Rshift),
+            parse_binop(parse_wire, " LROTATE ", uint, Op::Lrotate),
+            parse_binop(parse_wire, " RROTATE ", uint, Op::Rrotate),
+        ));
+        let parse_op = separated_pair(take_until(" -> "), tag(" -> "), parse_string);
+        let (input, (op_input, dst)) = all_consuming(parse_op)(input)?;
+        let (_, op) = all_consuming(op_parsers)(op_input)?;
+
+        Ok((input, (op, dst)))
+    }
+}
+
+/// The low `bits` bits of `n` rotated left by `by`. Rotating left by `by`
+/// bits is rotating right by `bits - by`.
+fn rotate_left(n: u64, by: u32, bits: u32) -> u64 {
+    match by % bits {
+        0 => n,
+        by => n << by | n >> (bits - by),
+    }
+}
+
+fn parse_wire(input: &str) -> IResult<&str, Wire> {
+    alt((map(uint, Wire::Lit), map(parse_string, Wire::Str)))(input)
+}
+
+fn parse_string(input: &str) -> IResult<&str, String> {
+    map(alpha1, String::from)(input)
+}
+
+fn parse_binop<'a, A, B>(
+    left: impl FnMut(&'a str) -> IResult<&'a str, A>,
+    sep: &'a str,
+    right: impl FnMut(&'a str) -> IResult<&'a str, B>,
+    mut f: impl FnMut(A, B) -> Op,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Op> {
+    map(separated_pair(left, tag(sep), right), move |(a, b)| f(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_op_parse() {
+        assert_eq!(
+            Op::parse("NOT x -> h").unwrap().1,
+            (Op::Not("x".into()), "h".into())
+        );
+
+        assert_eq!(
+            Op::parse("123 -> x").unwrap().1,
+            (Op::Id(123.into()), "x".into())
+        );
+        assert_eq!(
+            Op::parse("456 -> y").unwrap().1,
+            (Op::Id(456.into()), "y".into())
+        );
+        assert_eq!(
+            Op::parse("x AND y -> d").unwrap().1,
+            (Op::And("x".into(), "y".into()), "d".into())
+        );
+        assert_eq!(
+            Op::parse("x OR y -> e").unwrap().1,
+            (Op::Or("x".into(), "y".into()), "e".into())
+        );
+        assert_eq!(
+            Op::parse("x LSHIFT 2 -> f").unwrap().1,
+            (Op::Lshift("x".into(), 2), "f".into())
+        );
+        assert_eq!(
+            Op::parse("y RSHIFT 2 -> g").unwrap().1,
+            (Op::Rshift("y".into(), 2), "g".into())
+        );
+        assert_eq!(
+            Op::parse("NOT x -> h").unwrap().1,
+            (Op::Not("x".into()), "h".into())
+        );
+        assert_eq!(
+            Op::parse("NOT y -> i").unwrap().1,
+            (Op::Not("y".into()), "i".into())
+        );
+
+        assert_eq!(
+            Op::parse("1 AND fi -> fj").unwrap().1,
+            (Op::And((1).into(), "fi".into()), "fj".into())
+        );
+    }
+
+    #[test]
+    fn test_emu() {
+        let mut emu = Emu::default();
+        emu.wire(Op::Id(123.into()), "x".into());
+        emu.wire(Op::Id(456.into()), "y".into());
+        emu.wire(Op::And("x".into(), "y".into()), "d".into());
+        emu.wire(Op::Or("x".into(), "y".into()), "e".into());
+        emu.wire(Op::Lshift("x".into(), 2), "f".into());
+        emu.wire(Op::Rshift("y".into(), 2), "g".into());
+        emu.wire(Op::Not("x".into()), "h".into());
+        emu.wire(Op::Not("y".into()), "i".into());
+
+        assert_eq!(emu.resolve("d").unwrap(), 72);
+        assert_eq!(emu.resolve("e").unwrap(), 507);
+        assert_eq!(emu.resolve("f").unwrap(), 492);
+        assert_eq!(emu.resolve("g").unwrap(), 114);
+        assert_eq!(emu.resolve("h").unwrap(), 65412);
+        assert_eq!(emu.resolve("i").unwrap(), 65079);
+        assert_eq!(emu.resolve("x").unwrap(), 123);
+        assert_eq!(emu.resolve("y").unwrap(), 456);
+    }
+
+    #[test]
+    fn test_emu_errors() {
+        let mut emu = parse_circuit("b -> a\nc AND a -> b\nNOT b -> c\nd -> e").unwrap();
+        assert_eq!(
+            emu.resolve("a").unwrap_err().to_string(),
+            "unsolvable: wires b -> c -> b form a cycle"
+        );
+
+        let e = emu.resolve("e").unwrap_err().to_string();
+        assert_eq!(e, "unsolvable: wire d read by e is undefined");
+        assert_eq!(emu.cycle("a"), ["a", "b", "a"]);
+
+        // deep enough to overflow the stack if evaluated recursively
+        let mut emu = Emu::default();
+        emu.wire(Op::Id(1.into()), "w0".into());
+        for i in 1..100_000 {
+            let prev = format!("w{}", i - 1);
+            emu.wire(Op::Not(prev.as_str().into()), format!("w{i}"));
+        }
+        assert_eq!(emu.resolve("w99999").unwrap(), 0xfffe);
+    }
+
+    #[test]
+    fn test_parse_circuit() {
+        let Err(Error::Parse(e)) = parse_circuit("123 -> x\nx ANDD y -> d") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (2, 2));
+        assert_eq!(e.text, "x ANDD y -> d");
+
+        for (input, column) in [("NOT x y -> d", 6), ("x AND 5y -> d", 8)] {
+            let Err(Error::Parse(e)) = parse_circuit(input) else {
+                panic!("expected a parse error for {input:?}");
+            };
+            assert_eq!((e.line, e.column), (1, column), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_gates() {
+        let input = "202 -> x\n\
+            99 -> y\n\
+            x XOR y -> a\n\
+            x NAND y -> b\n\
+            x NOR y -> c\n\
+            x ADD y -> d\n\
+            y SUB x -> e\n\
+            x LROTATE 3 -> f\n\
+            x RROTATE 3 -> g\n\
+            x LROTATE 11 -> h\n\
+            x LSHIFT 3 -> i";
+        let mut emu = Emu::parse(input, Width::W8).unwrap();
+
+        let signals =
+            ["a", "b", "c", "d", "e", "f", "g", "h", "i"].map(|w| emu.resolve(w).unwrap());
+        assert_eq!(
+            signals,
+            [
+                202 ^ 99,
+                !(202 & 99) & 0xff,
+                !(202 | 99) & 0xff,
+                (202 + 99) % 256,
+                256 + 99 - 202,
+                0b0101_0110,
+                0b0101_1001,
+                0b0101_0110,
+                0b0101_0000,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_widths() {
+        let input = "65535 -> x\nx ADD 1 -> y\nNOT x -> z";
+        for (width, y, z) in [
+            (Width::W16, 0, 0),
+            (Width::W32, 65536, 0xffff_0000),
+            (Width::W64, 65536, 0xffff_ffff_ffff_0000),
+        ] {
+            let mut emu = Emu::parse(input, width).unwrap();
+            assert_eq!(emu.resolve("y").unwrap(), y, "{width:?}");
+            assert_eq!(emu.resolve("z").unwrap(), z, "{width:?}");
+        }
+
+        let Err(Error::Parse(e)) = Emu::parse(input, Width::W8) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (1, 1));
+        assert!(e.to_string().contains("65535 doesn't fit in 8 bits"));
+
+        let Err(Error::Parse(e)) = Emu::parse("1 AND 100000 -> b", Width::W16) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (1, 7));
+    }
+
+    #[test]
+    fn test_p1() {
+        let input = "123 -> x\n\
+            456 -> y\n\
+            x AND y -> d\n\
+            x OR y -> e\n\
+            x LSHIFT 2 -> f\n\
+            y RSHIFT 2 -> g\n\
+            NOT x -> a\n\
+            NOT y -> i\n";
+
+        assert_eq!(p1(&parse_circuit(input).unwrap()).unwrap(), 65412);
+    }
+
+    #[test]
+    fn test_p2() {
+        let emu = parse_circuit("300 -> b\nb AND 255 -> c\nc LSHIFT 1 -> a").unwrap();
+
+        assert_eq!(p1(&emu).unwrap(), 88);
+        // b = 88 this time round, wiping the signals that depend on it
+        assert_eq!(p2(&emu).unwrap(), 176);
+    }
+}
+
+crate::solve!(2015, 7, "Some Assembly Required", parse_circuit, p1, p2);
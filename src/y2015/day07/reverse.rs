@@ -0,0 +1,386 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::{rotate_left, Emu, Op, Width, Wire};
+use crate::{
+    graph::{topo_sort, Graph},
+    Error,
+};
+
+/// Free wires with at most this many bits between them are searched
+/// exhaustively, wider ones a bit at a time.
+const EXHAUSTIVE_BITS: u32 = 8;
+
+/// The signals driven on the free wires, by wire.
+pub type Assignment = BTreeMap<String, u64>;
+
+/// How many assignments `Emu::solve_for` looks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Solutions {
+    First,
+    All,
+}
+
+impl Emu {
+    /// The signals to drive on the `free` wires, instead of their gates, for
+    /// every wire in `targets` to carry its signal. Every wire the targets
+    /// depend on must be either driven by a gate or free.
+    pub fn solve_for(
+        &self,
+        free: &[&str],
+        targets: &[(&str, u64)],
+        solutions: Solutions,
+    ) -> Result<Vec<Assignment>, Error> {
+        let exhaustive = free.len() as u32 * self.width.bits() <= EXHAUSTIVE_BITS;
+        let search = Search::new(self, free, targets, solutions)?;
+
+        Ok(if exhaustive {
+            search.exhaustive()
+        } else {
+            search.propagate()
+        })
+    }
+}
+
+struct Search<'a> {
+    emu: &'a Emu,
+    free: Vec<&'a str>,
+    targets: &'a [(&'a str, u64)],
+    solutions: Solutions,
+    /// The wires the targets depend on, each after the wires it reads.
+    order: Vec<String>,
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        emu: &'a Emu,
+        free: &'a [&'a str],
+        targets: &'a [(&'a str, u64)],
+        solutions: Solutions,
+    ) -> Result<Self, Error> {
+        let cut = Cut {
+            emu,
+            free: free.iter().copied().collect(),
+        };
+        let roots = targets.iter().map(|(w, _)| w.to_string());
+        let mut order = topo_sort(&cut, roots).map_err(|w| {
+            Error::unsolvable(format!("wires {} form a cycle", emu.cycle(&w).join(" -> ")))
+        })?;
+        order.reverse();
+
+        if let Some(w) = order
+            .iter()
+            .find(|w| !cut.free.contains(w.as_str()) && !emu.logic.contains_key(*w))
+        {
+            return Err(Error::unsolvable(format!(
+                "wire {w} is undefined and not free"
+            )));
+        }
+        if let Some(w) = free.iter().find(|w| !order.iter().any(|o| o == *w)) {
+            return Err(Error::unsolvable(format!(
+                "free wire {w} doesn't drive any target"
+            )));
+        }
+
+        Ok(Self {
+            emu,
+            free: free.to_vec(),
+            targets,
+            solutions,
+            order,
+        })
+    }
+
+    /// Tries every signal on every free wire.
+    fn exhaustive(&self) -> Vec<Assignment> {
+        let (width, bits) = (self.emu.width, self.emu.width.bits());
+        let mut found = vec![];
+        let mut signals = HashMap::new();
+
+        for n in 0..1u64 << (self.free.len() as u32 * bits) {
+            for (i, w) in self.free.iter().enumerate() {
+                signals.insert(*w, n >> (i as u32 * bits) & width.mask());
+            }
+            for w in &self.order {
+                if !self.free.contains(&w.as_str()) {
+                    let n = self.emu.logic[w].eval(width, |input| signals[input]);
+                    signals.insert(w, n);
+                }
+            }
+
+            if self.targets.iter().all(|(w, n)| signals[w] == *n) {
+                found.push(self.assignment(|w| signals[w]));
+                if self.solutions == Solutions::First {
+                    break;
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Fixes the free wires a bit at a time, lowest bits first, giving up on
+    /// a partial assignment as soon as it sets a target bit wrong.
+    fn propagate(&self) -> Vec<Assignment> {
+        let mut found = vec![];
+        let mut free = vec![Bits::unknown(self.emu.width); self.free.len()];
+        self.branch(0, &mut free, &mut found);
+
+        found
+    }
+
+    /// Searches the assignments agreeing with `free`, whose first `depth`
+    /// bits are known.
+    fn branch(&self, depth: u32, free: &mut [Bits], found: &mut Vec<Assignment>) -> bool {
+        let mut signals = HashMap::new();
+        for (w, bits) in self.free.iter().zip(free.iter()) {
+            signals.insert(*w, *bits);
+        }
+        for w in &self.order {
+            if !self.free.contains(&w.as_str()) {
+                let bits = self.emu.logic[w].propagate(self.emu.width, |input| signals[input]);
+                signals.insert(w, bits);
+            }
+        }
+        if self.targets.iter().any(|(w, n)| !signals[w].allows(*n)) {
+            return false;
+        }
+
+        if depth == self.free.len() as u32 * self.emu.width.bits() {
+            found.push(self.assignment(|w| signals[w].value));
+            return self.solutions == Solutions::First;
+        }
+
+        // bit by bit, each bit on every free wire in turn
+        let (bit, i) = (depth / free.len() as u32, depth as usize % free.len());
+        for value in [0, 1] {
+            let before = free[i];
+            free[i] = before.set(bit, value);
+            let done = self.branch(depth + 1, free, found);
+            free[i] = before;
+            if done {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn assignment(&self, signal: impl Fn(&str) -> u64) -> Assignment {
+        self.free
+            .iter()
+            .map(|w| (w.to_string(), signal(w)))
+            .collect()
+    }
+}
+
+/// The circuit with the gates driving the free wires cut off.
+struct Cut<'a> {
+    emu: &'a Emu,
+    free: HashSet<&'a str>,
+}
+
+impl Graph for Cut<'_> {
+    type Node = String;
+    type Cost = usize;
+
+    fn neighbours(&self, wire: &String) -> impl Iterator<Item = (String, usize)> {
+        let cut = self.free.contains(wire.as_str());
+        self.emu.neighbours(wire).filter(move |_| !cut)
+    }
+}
+
+/// A signal of which only some bits are known. Bits beyond the width of the
+/// wire are known to be 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bits {
+    known: u64,
+    value: u64,
+}
+
+impl Bits {
+    fn exact(n: u64, width: Width) -> Self {
+        Self {
+            known: u64::MAX,
+            value: n & width.mask(),
+        }
+    }
+
+    fn unknown(width: Width) -> Self {
+        Self {
+            known: !width.mask(),
+            value: 0,
+        }
+    }
+
+    fn set(self, bit: u32, value: u64) -> Self {
+        Self {
+            known: self.known | 1 << bit,
+            value: self.value | value << bit,
+        }
+    }
+
+    /// Whether the signal could be `n`.
+    fn allows(self, n: u64) -> bool {
+        (self.value ^ n) & self.known == 0
+    }
+
+    /// Zeroes the unknown bits and the bits beyond the width.
+    fn masked(self, width: Width) -> Self {
+        Self {
+            known: self.known | !width.mask(),
+            value: self.value & self.known & width.mask(),
+        }
+    }
+}
+
+impl Op {
+    /// The bits of the output of the gate that are known given the known
+    /// bits of its inputs.
+    fn propagate(&self, width: Width, signal: impl Fn(&str) -> Bits) -> Bits {
+        let operand = |w: &Wire| match w {
+            Wire::Lit(n) => Bits::exact(*n, width),
+            Wire::Str(s) => signal(s),
+        };
+        let bits = width.bits();
+        let and = |a: Bits, b: Bits| Bits {
+            // a known 0 on either side is enough
+            known: a.known & b.known | a.known & !a.value | b.known & !b.value,
+            value: a.value & b.value,
+        };
+        let or = |a: Bits, b: Bits| Bits {
+            known: a.known & b.known | a.known & a.value | b.known & b.value,
+            value: a.value | b.value,
+        };
+        let not = |a: Bits| Bits {
+            known: a.known,
+            value: !a.value,
+        };
+        // the low bits of a sum only depend on the low bits of its terms
+        let low = |a: Bits, b: Bits, f: fn(u64, u64) -> u64| Bits {
+            known: match (a.known & b.known).trailing_ones() {
+                64 => u64::MAX,
+                n => (1 << n) - 1,
+            },
+            value: f(a.value, b.value),
+        };
+        let rotated = |a: Bits, by: u32| Bits {
+            known: rotate_left(a.known & width.mask(), by, bits),
+            value: rotate_left(a.value, by, bits),
+        };
+
+        let out = match self {
+            Op::Id(src) => operand(src),
+            Op::Not(src) => not(operand(src)),
+            Op::And(a, b) => and(operand(a), operand(b)),
+            Op::Or(a, b) => or(operand(a), operand(b)),
+            Op::Xor(a, b) => {
+                let (a, b) = (operand(a), operand(b));
+                Bits {
+                    known: a.known & b.known,
+                    value: a.value ^ b.value,
+                }
+            }
+            Op::Nand(a, b) => not(and(operand(a), operand(b))),
+            Op::Nor(a, b) => not(or(operand(a), operand(b))),
+            Op::Add(a, b) => low(operand(a), operand(b), u64::wrapping_add),
+            Op::Sub(a, b) => low(operand(a), operand(b), u64::wrapping_sub),
+            Op::Lshift(src, by) => {
+                let (a, by) = (operand(src), u32::from(*by));
+                Bits {
+                    // the bits shifted in are 0
+                    known: a.known.checked_shl(by).unwrap_or(0)
+                        | !u64::MAX.checked_shl(by).unwrap_or(0),
+                    value: a.value.checked_shl(by).unwrap_or(0),
+                }
+            }
+            Op::Rshift(src, by) => {
+                let (a, by) = (operand(src), u32::from(*by));
+                Bits {
+                    known: a.known.checked_shr(by).unwrap_or(0)
+                        | !u64::MAX.checked_shr(by).unwrap_or(0),
+                    value: a.value.checked_shr(by).unwrap_or(0),
+                }
+            }
+            Op::Lrotate(src, by) => rotated(operand(src), (*by).into()),
+            Op::Rrotate(src, by) => rotated(operand(src), bits - u32::from(*by) % bits),
+        };
+
+        out.masked(width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "x ADD y -> s\n\
+        x XOR 90 -> a\n\
+        s RROTATE 3 -> r\n\
+        r NAND a -> n\n\
+        y RSHIFT 2 -> z\n\
+        n SUB z -> o";
+
+    #[test]
+    fn test_solve_for() {
+        let emu = Emu::parse(INPUT, Width::W8).unwrap();
+        let solutions = emu.solve_for(&["x"], &[("a", 95)], Solutions::All).unwrap();
+        assert_eq!(solutions, [Assignment::from([("x".into(), 5)])]);
+
+        let e = emu
+            .solve_for(&["x"], &[("o", 0)], Solutions::First)
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "unsolvable: wire y is undefined and not free"
+        );
+        let e = emu
+            .solve_for(&["x", "y"], &[("a", 0)], Solutions::First)
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "unsolvable: free wire y doesn't drive any target"
+        );
+
+        let mut emu = emu;
+        for n in [0, 17, 255] {
+            let found = emu
+                .solve_for(&["x", "y"], &[("o", n)], Solutions::First)
+                .unwrap();
+            emu.override_wire("x", found[0]["x"]);
+            emu.override_wire("y", found[0]["y"]);
+            assert_eq!(emu.resolve("o").unwrap(), n);
+        }
+    }
+
+    #[test]
+    fn test_propagate() {
+        // the same solutions as an exhaustive search, but searched by bits
+        let emu = Emu::parse(INPUT, Width::W8).unwrap();
+        for n in [3, 200] {
+            let targets = [("o", n)];
+            let search = Search::new(&emu, &["x", "y"], &targets, Solutions::All).unwrap();
+            let (mut propagated, mut exhaustive) = (search.propagate(), search.exhaustive());
+            propagated.sort();
+            exhaustive.sort();
+            assert_eq!(propagated, exhaustive, "o = {n}");
+        }
+
+        // far too wide to search exhaustively
+        let mut emu = Emu::parse(INPUT, Width::W64).unwrap();
+        emu.override_wire("x", 0x0123_4567_89ab_cdef);
+        emu.override_wire("y", 1 << 40);
+        let targets = [("o", emu.resolve("o").unwrap()), ("y", 1 << 40)];
+        let found = emu
+            .solve_for(&["x", "y"], &targets, Solutions::First)
+            .unwrap();
+        emu.override_wire("x", found[0]["x"]);
+        assert_eq!(emu.resolve("o").unwrap(), targets[0].1);
+
+        let emu = Emu::parse("x AND 4294963200 -> a", Width::W32).unwrap();
+        let found = emu
+            .solve_for(&["x"], &[("a", 0xabcd_e000)], Solutions::All)
+            .unwrap();
+        assert_eq!(found.len(), 1 << 12);
+        assert!(found.iter().all(|s| s["x"] >> 12 == 0xabcde));
+    }
+}
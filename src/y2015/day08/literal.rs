@@ -0,0 +1,298 @@
+use std::{fmt, iter::Peekable, str::CharIndices};
+
+/// Which escapes a string literal may use. Santa's list only knows `\\`,
+/// `\"` and `\xHH`, the latter standing for the char U+00HH.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dialect {
+    #[default]
+    Santa,
+    /// Rust string literals, with `\n`, `\r`, `\t`, `\0`, `\'`, `\x00` to
+    /// `\x7f` and `\u{...}`.
+    Rust,
+    /// JSON strings, with `\/`, `\b`, `\f`, `\n`, `\r`, `\t` and `\uXXXX`,
+    /// surrogate pairs included.
+    Json,
+}
+
+/// Why a literal couldn't be decoded, at the byte `offset` of the literal
+/// where the trouble starts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscapeError {
+    pub offset: usize,
+    pub kind: ErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The literal doesn't start with a quote.
+    Unquoted,
+    /// The literal ends before its closing quote.
+    Unterminated,
+    /// A quote ends the literal before its end.
+    TrailingText,
+    /// A backslash followed by a char with no escape in the dialect.
+    UnknownEscape(char),
+    /// `\x` not followed by two hex digits.
+    BadHex,
+    /// A `\x` escape above `\x7f`, where only ASCII is allowed.
+    NotAscii,
+    /// A `\u` escape not written the way the dialect wants.
+    BadUnicode,
+    /// A `\u` escape for something that isn't a char, such as a lone
+    /// surrogate.
+    NotAChar(u32),
+    /// A control char that must be escaped.
+    Unescaped(char),
+}
+
+impl fmt::Display for EscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::Unquoted => write!(f, "expected a quote")?,
+            ErrorKind::Unterminated => write!(f, "missing closing quote")?,
+            ErrorKind::TrailingText => write!(f, "text after the closing quote")?,
+            ErrorKind::UnknownEscape(c) => write!(f, "unknown escape \\{}", c.escape_debug())?,
+            ErrorKind::BadHex => write!(f, "expected two hex digits after \\x")?,
+            ErrorKind::NotAscii => write!(f, "\\x escape above \\x7f")?,
+            ErrorKind::BadUnicode => write!(f, "malformed \\u escape")?,
+            ErrorKind::NotAChar(n) => write!(f, "{n:#x} isn't a char")?,
+            ErrorKind::Unescaped(c) => write!(f, "unescaped {c:?}")?,
+        }
+        write!(f, " at byte {}", self.offset)
+    }
+}
+
+impl Dialect {
+    /// The string a quoted literal stands for.
+    pub fn decode(self, literal: &str) -> Result<String, EscapeError> {
+        let mut chars = literal.char_indices().peekable();
+        let mut decoded = String::new();
+        if chars.next().map(|(_, c)| c) != Some('"') {
+            return Err(error(0, ErrorKind::Unquoted));
+        }
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' if i + 1 == literal.len() => return Ok(decoded),
+                '"' => return Err(error(i + 1, ErrorKind::TrailingText)),
+                '\\' => decoded.push(self.unescape(i, &mut chars)?),
+                c if self == Dialect::Json && c < ' ' => {
+                    return Err(error(i, ErrorKind::Unescaped(c)))
+                }
+                c => decoded.push(c),
+            }
+        }
+
+        Err(error(literal.len(), ErrorKind::Unterminated))
+    }
+
+    /// `s` as a quoted literal that decodes back to `s`. Santa only escapes
+    /// `"` and `\`, as the puzzle does, while Rust and JSON escape control
+    /// chars too.
+    pub fn encode(self, s: &str) -> String {
+        let mut encoded = String::from('"');
+
+        for c in s.chars() {
+            match (self, c) {
+                (_, '"') => encoded.push_str("\\\""),
+                (_, '\\') => encoded.push_str("\\\\"),
+                (Dialect::Rust | Dialect::Json, '\n') => encoded.push_str("\\n"),
+                (Dialect::Rust | Dialect::Json, '\r') => encoded.push_str("\\r"),
+                (Dialect::Rust | Dialect::Json, '\t') => encoded.push_str("\\t"),
+                (Dialect::Rust, '\0') => encoded.push_str("\\0"),
+                (Dialect::Json, '\u{8}') => encoded.push_str("\\b"),
+                (Dialect::Json, '\u{c}') => encoded.push_str("\\f"),
+                (Dialect::Json, c) if c < ' ' => encoded.push_str(&format!("\\u{:04x}", c as u32)),
+                (Dialect::Rust, c) if c.is_ascii_control() => {
+                    encoded.push_str(&format!("\\x{:02x}", c as u32))
+                }
+                (_, c) => encoded.push(c),
+            }
+        }
+
+        encoded.push('"');
+        encoded
+    }
+
+    /// The char escaped by the backslash at `at`.
+    fn unescape(self, at: usize, chars: &mut Peekable<CharIndices>) -> Result<char, EscapeError> {
+        let Some((_, c)) = chars.next() else {
+            return Err(error(at, ErrorKind::Unterminated));
+        };
+
+        let unescaped = match (self, c) {
+            (_, '"' | '\\') => c,
+            (Dialect::Santa | Dialect::Rust, 'x') => {
+                let n = hex(chars, 2).ok_or(error(at, ErrorKind::BadHex))?;
+                if self == Dialect::Rust && n > 0x7f {
+                    return Err(error(at, ErrorKind::NotAscii));
+                }
+                char::from(n as u8)
+            }
+            (Dialect::Rust, '\'') => '\'',
+            (Dialect::Rust, '0') => '\0',
+            (Dialect::Rust | Dialect::Json, 'n') => '\n',
+            (Dialect::Rust | Dialect::Json, 'r') => '\r',
+            (Dialect::Rust | Dialect::Json, 't') => '\t',
+            (Dialect::Rust, 'u') => {
+                let n = braced_hex(chars).ok_or(error(at, ErrorKind::BadUnicode))?;
+                char::from_u32(n).ok_or(error(at, ErrorKind::NotAChar(n)))?
+            }
+            (Dialect::Json, '/') => '/',
+            (Dialect::Json, 'b') => '\u{8}',
+            (Dialect::Json, 'f') => '\u{c}',
+            (Dialect::Json, 'u') => {
+                let unit = hex(chars, 4).ok_or(error(at, ErrorKind::BadUnicode))?;
+                let n = match unit {
+                    // a high surrogate must be followed by a low one
+                    0xd800..=0xdbff => {
+                        let low = surrogate(chars).ok_or(error(at, ErrorKind::NotAChar(unit)))?;
+                        0x10000 + ((unit - 0xd800) << 10 | (low - 0xdc00))
+                    }
+                    n => n,
+                };
+                char::from_u32(n).ok_or(error(at, ErrorKind::NotAChar(n)))?
+            }
+            (_, c) => return Err(error(at, ErrorKind::UnknownEscape(c))),
+        };
+
+        Ok(unescaped)
+    }
+}
+
+fn error(offset: usize, kind: ErrorKind) -> EscapeError {
+    EscapeError { offset, kind }
+}
+
+/// The value of the next `digits` hex digits.
+fn hex(chars: &mut Peekable<CharIndices>, digits: usize) -> Option<u32> {
+    (0..digits).try_fold(0, |n, _| {
+        let (_, c) = chars.next_if(|(_, c)| c.is_ascii_hexdigit())?;
+        Some(n << 4 | c.to_digit(16)?)
+    })
+}
+
+/// The value of the `{...}` part of a Rust `\u{...}` escape: one to six hex
+/// digits, maybe separated by underscores.
+fn braced_hex(chars: &mut Peekable<CharIndices>) -> Option<u32> {
+    chars.next_if(|&(_, c)| c == '{')?;
+    let (mut n, mut digits) = (0, 0);
+
+    loop {
+        match chars.next()? {
+            (_, '}') if digits > 0 => return Some(n),
+            (_, '_') if digits > 0 => {}
+            (_, c) if digits < 6 => {
+                n = n << 4 | c.to_digit(16)?;
+                digits += 1;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// The low surrogate of a JSON `\uXXXX` escape next in `chars`.
+fn surrogate(chars: &mut Peekable<CharIndices>) -> Option<u32> {
+    chars.next_if(|&(_, c)| c == '\\')?;
+    chars.next_if(|&(_, c)| c == 'u')?;
+    hex(chars, 4).filter(|n| (0xdc00..=0xdfff).contains(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            Dialect::Santa.decode(r#""a\\b\"c\x27\xe9""#).unwrap(),
+            "a\\b\"c'é"
+        );
+        assert_eq!(
+            Dialect::Rust
+                .decode(r#""\n\r\t\0\'\x7f\u{1F6_00}""#)
+                .unwrap(),
+            "\n\r\t\0'\x7f😀"
+        );
+        assert_eq!(
+            Dialect::Json.decode(r#""\/\b\fé😀""#).unwrap(),
+            "/\u{8}\u{c}é😀"
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let cases = [
+            (Dialect::Santa, "abc", 0, ErrorKind::Unquoted),
+            (Dialect::Santa, r#""abc"#, 4, ErrorKind::Unterminated),
+            (Dialect::Santa, r#""abc\"#, 4, ErrorKind::Unterminated),
+            (Dialect::Santa, r#""a"bc""#, 3, ErrorKind::TrailingText),
+            (
+                Dialect::Santa,
+                r#""ab\n""#,
+                3,
+                ErrorKind::UnknownEscape('n'),
+            ),
+            (Dialect::Santa, r#""\x4g""#, 1, ErrorKind::BadHex),
+            (Dialect::Rust, r#""ok\x80""#, 3, ErrorKind::NotAscii),
+            (Dialect::Rust, r#""\u{}""#, 1, ErrorKind::BadUnicode),
+            (Dialect::Rust, r#""\u{1234567}""#, 1, ErrorKind::BadUnicode),
+            (
+                Dialect::Rust,
+                r#""\u{d800}""#,
+                1,
+                ErrorKind::NotAChar(0xd800),
+            ),
+            (Dialect::Json, r#""\x41""#, 1, ErrorKind::UnknownEscape('x')),
+            (Dialect::Json, r#""\u12""#, 1, ErrorKind::BadUnicode),
+            (
+                Dialect::Json,
+                r#""\ud83d!""#,
+                1,
+                ErrorKind::NotAChar(0xd83d),
+            ),
+            (Dialect::Json, r#""\ude00""#, 1, ErrorKind::NotAChar(0xde00)),
+            (Dialect::Json, "\"a\tb\"", 2, ErrorKind::Unescaped('\t')),
+        ];
+
+        for (dialect, literal, offset, kind) in cases {
+            assert_eq!(
+                dialect.decode(literal),
+                Err(EscapeError { offset, kind }),
+                "{dialect:?} {literal}"
+            );
+        }
+        let e = Dialect::Santa.decode(r#""ab\n""#).unwrap_err();
+        assert_eq!(e.to_string(), "unknown escape \\n at byte 3");
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            Dialect::Santa.encode("\"aaa\\\"aaa\""),
+            r#""\"aaa\\\"aaa\"""#
+        );
+        assert_eq!(Dialect::Santa.encode("é\n😀"), "\"é\n😀\"");
+        assert_eq!(Dialect::Rust.encode("é\n\u{1}'"), r#""é\n\x01'""#);
+        assert_eq!(Dialect::Json.encode("é\u{8}\u{1}/"), r#""é\b\u0001/""#);
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(s in any::<String>()) {
+            for dialect in [Dialect::Santa, Dialect::Rust, Dialect::Json] {
+                prop_assert_eq!(dialect.decode(&dialect.encode(&s)), Ok(s.clone()));
+            }
+        }
+
+        #[test]
+        fn test_rust_literals(s in any::<String>()) {
+            let literal = Dialect::Rust.encode(&s);
+            let debug = format!("{s:?}");
+            prop_assert_eq!(Dialect::Rust.decode(&debug), Ok(s));
+            prop_assert_eq!(Dialect::Rust.decode(&literal), Dialect::Rust.decode(&debug));
+        }
+    }
+}
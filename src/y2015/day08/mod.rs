@@ -0,0 +1,74 @@
+use crate::{parse::lines, Error};
+
+mod literal;
+
+pub use literal::{Dialect, ErrorKind, EscapeError};
+
+pub fn p1(input: &str) -> Result<usize, Error> {
+    lines(input).try_fold(0, |acc, l| {
+        let decoded = Dialect::Santa
+            .decode(l.text)
+            .map_err(|e| l.error(&l.text[e.offset..], e.to_string()))?;
+        Ok(acc + code_len(l.text) - decoded.chars().count())
+    })
+}
+
+pub fn p2(input: &str) -> usize {
+    input
+        .lines()
+        .fold(0, |acc, l| acc + escaped_len(l) - code_len(l))
+}
+
+fn code_len(input: &str) -> usize {
+    input.chars().count()
+}
+
+fn escaped_len(input: &str) -> usize {
+    Dialect::Santa.encode(input).chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p1() {
+        assert_eq!(code_len("\"\""), 2);
+        assert_eq!(code_len("\"abc\""), 5);
+        assert_eq!(code_len("\"aaa\\\"aaa\""), 10);
+        assert_eq!(code_len("\"\\x27\""), 6);
+        assert_eq!(code_len("\"qsmzhnx\\\"\""), 11);
+
+        assert_eq!(
+            p1("\"\"\n\"abc\"\n\"aaa\\\"aaa\"\n\"\\x27\"\n").unwrap(),
+            12
+        );
+        assert_eq!(p1("\"qsmzhnx\\\"\"").unwrap(), 3);
+
+        let Err(Error::Parse(e)) = p1("\"abc\"\n\"a\\qb\"") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (2, 3));
+        assert_eq!(e.reason, "unknown escape \\q at byte 2");
+    }
+
+    #[test]
+    fn test_p2() {
+        assert_eq!(escaped_len("\"\""), 6);
+        assert_eq!(escaped_len("\"abc\""), 9);
+        assert_eq!(escaped_len("\"aaa\\\"aaa\""), 16);
+        assert_eq!(escaped_len("\"\\x27\""), 11);
+        // only quotes and backslashes get escaped
+        assert_eq!(escaped_len("\"é\""), 7);
+
+        assert_eq!(
+            p2("\"\"\n\
+                \"abc\"\n\
+                \"aaa\\\"aaa\"\n\
+                \"\\x27\"\n"),
+            19
+        );
+    }
+}
+
+crate::solve!(2015, 8, "Matchsticks", p1, p2);
@@ -1,34 +1,45 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
 
-pub fn p1(input: &str) -> usize {
-    let (routes, locset) = parse_routes(input);
-    shortest_path(&routes, &locset)
+use crate::{
+    graph::{held_karp, Objective, Tour},
+    Error, ParseError,
+};
+
+pub type Input<'a> = (RouteMap<'a>, LocSet<'a>);
+
+pub fn p1((routes, locset): &Input) -> Result<usize, Error> {
+    shortest_path(routes, locset)
 }
 
-pub fn p2(input: &str) -> usize {
-    let (routes, locset) = parse_routes(input);
-    longest_path(&routes, &locset)
+pub fn p2((routes, locset): &Input) -> Result<usize, Error> {
+    longest_path(routes, locset)
 }
 
 type Loc<'a> = &'a str;
 type Dist = usize;
-type RouteMap<'a> = HashMap<Loc<'a>, HashMap<Loc<'a>, Dist>>;
-type LocSet<'a> = HashSet<Loc<'a>>;
+pub type RouteMap<'a> = HashMap<Loc<'a>, HashMap<Loc<'a>, Dist>>;
+pub type LocSet<'a> = HashSet<Loc<'a>>;
 
-fn parse_routes(input: &str) -> (RouteMap<'_>, LocSet<'_>) {
+fn parse_routes(input: &str) -> Result<Input<'_>, Error> {
     let mut map = RouteMap::default();
     let mut locs = LocSet::default();
 
-    for l in input.lines() {
-        let (loc1, rest) = l.split_once(" to ").unwrap();
-        let (loc2, rest) = rest.split_once(" = ").unwrap();
-        let dist = rest.parse::<usize>().unwrap();
+    for (i, l) in input.lines().enumerate() {
+        let (loc1, rest) = l
+            .split_once(" to ")
+            .ok_or_else(|| ParseError::at(i, l, "", "expected `A to B = N`"))?;
+        let (loc2, rest) = rest
+            .split_once(" = ")
+            .ok_or_else(|| ParseError::at(i, l, rest, "expected `B = N`"))?;
+        let dist = rest
+            .parse::<usize>()
+            .map_err(|e| ParseError::at(i, l, rest, e.to_string()))?;
 
         add_route(&mut map, &mut locs, loc1, loc2, dist);
         add_route(&mut map, &mut locs, loc2, loc1, dist);
     }
 
-    (map, locs)
+    Ok((map, locs))
 }
 
 fn add_route<'a>(
@@ -42,55 +53,27 @@ fn add_route<'a>(
     map.entry(src).or_default().insert(dst, dist);
 }
 
-fn shortest_path(routes: &RouteMap<'_>, locset: &LocSet<'_>) -> usize {
-    fold_path(routes, locset, usize::MAX, |acc, dist| dist < acc)
+fn shortest_path(routes: &RouteMap<'_>, locset: &LocSet<'_>) -> Result<usize, Error> {
+    fold_path(routes, locset, Objective::Min)
 }
 
-fn longest_path(routes: &RouteMap<'_>, locset: &LocSet<'_>) -> usize {
-    fold_path(routes, locset, 0, |acc, dist| dist > acc)
+fn longest_path(routes: &RouteMap<'_>, locset: &LocSet<'_>) -> Result<usize, Error> {
+    fold_path(routes, locset, Objective::Max)
 }
 
+/// The best route visiting every location once, starting anywhere.
 fn fold_path(
     routes: &RouteMap<'_>,
     locset: &LocSet<'_>,
-    mut acc: usize,
-    predicate: fn(usize, usize) -> bool,
-) -> usize {
-    let mut best_path = vec![];
-    let path_len = locset.len();
-
-    // any starting point is possible
-    for &start in locset {
-        let path = vec![start];
-        let mut track = VecDeque::from([(path, LocSet::from([start]), 0)]);
-
-        while let Some((path, visits, total_dist)) = track.pop_front() {
-            // cheap way to check that we've visited every location
-            if path.len() == path_len && predicate(acc, total_dist) {
-                acc = total_dist;
-                best_path = path;
-                continue;
-            }
-
-            let node = path.last().unwrap();
-            let edges = routes.get(*node).unwrap();
-
-            // breadth first
-            for (edge, weight) in edges {
-                if visits.contains(edge) {
-                    continue;
-                }
-                track.push_back((
-                    path.iter().chain([edge]).cloned().collect(),
-                    visits.iter().chain([edge]).cloned().collect(),
-                    total_dist + weight,
-                ));
-            }
-        }
-    }
-
-    println!("{}", best_path.join(" -> "));
-    acc
+    objective: Objective,
+) -> Result<usize, Error> {
+    let mut locs: Vec<_> = locset.iter().collect();
+    locs.sort();
+
+    let dist = |a: usize, b: usize| routes.get(locs[a])?.get(locs[b]).copied();
+    held_karp(locs.len(), dist, objective, Tour::Open)
+        .map(|path| path.cost)
+        .ok_or_else(|| Error::unsolvable("no route visits every location"))
 }
 
 #[cfg(test)]
@@ -110,11 +93,11 @@ mod tests {
         ]);
         let expected_locs: LocSet = LocSet::from(["London", "Dublin", "Belfast"]);
 
-        let (routes, locs) = parse_routes(input);
+        let (routes, locs) = parse_routes(input).unwrap();
         assert_eq!(routes, expected_map);
         assert_eq!(locs, expected_locs);
 
-        assert_eq!(shortest_path(&routes, &locs), 605);
+        assert_eq!(shortest_path(&routes, &locs).unwrap(), 605);
     }
 
     #[test]
@@ -123,6 +106,8 @@ mod tests {
         London to Belfast = 518\n\
         Dublin to Belfast = 141";
 
-        assert_eq!(p2(input), 982);
+        assert_eq!(p2(&parse_routes(input).unwrap()).unwrap(), 982);
     }
 }
+
+crate::solve!(2015, 9, "All in a Single Night", parse_routes, p1, p2);
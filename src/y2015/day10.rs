@@ -19,22 +19,19 @@ pub fn p2(input: &str) -> usize {
 }
 
 fn look_and_say(input: &str) -> String {
-    let groups = input
-        .chars()
-        .into_iter()
-        .fold(VecDeque::new(), |mut acc, d| {
-            if let Some((d0, n)) = acc.back_mut() {
-                if *d0 == d {
-                    *n += 1;
-                } else {
-                    acc.push_back((d, 1));
-                }
+    let groups = input.chars().fold(VecDeque::new(), |mut acc, d| {
+        if let Some((d0, n)) = acc.back_mut() {
+            if *d0 == d {
+                *n += 1;
             } else {
-                acc.push_back((d, 1u8))
+                acc.push_back((d, 1));
             }
+        } else {
+            acc.push_back((d, 1u8))
+        }
 
-            acc
-        });
+        acc
+    });
 
     groups
         .into_iter()
@@ -58,3 +55,5 @@ mod tests {
         assert_eq!(look_and_say("1113222113"), "3113322113");
     }
 }
+
+crate::solve!(2015, 10, "Elves Look, Elves Say", p1, p2);
@@ -1,14 +1,16 @@
 use std::collections::HashSet;
 
-pub fn p1(input: &str) -> String {
+use crate::Error;
+
+pub fn p1(input: &str) -> Result<String, Error> {
     next_pass(input)
 }
 
-pub fn p2(input: &str) -> String {
-    next_pass(&next_pass(input))
+pub fn p2(input: &str) -> Result<String, Error> {
+    next_pass(&next_pass(input)?)
 }
 
-fn next_pass(s: &str) -> String {
+fn next_pass(s: &str) -> Result<String, Error> {
     let mut pass = (s.chars().next().unwrap() as u64) << (7 * 8)
         | (s.chars().nth(1).unwrap() as u64) << (6 * 8)
         | (s.chars().nth(2).unwrap() as u64) << (5 * 8)
@@ -34,11 +36,11 @@ fn next_pass(s: &str) -> String {
 
         chars = gen_chars(pass).collect();
         if has_3_straights(&chars) && has_2_pairs(&chars) && !has_forbidden_letters(&chars) {
-            return chars;
+            return Ok(chars);
         }
     }
 
-    panic!("too many iterations")
+    Err(Error::unsolvable(format!("no valid password follows {s}")))
 }
 
 fn gen_chars(n: u64) -> impl Iterator<Item = char> {
@@ -95,6 +97,8 @@ mod tests {
         assert!(has_2_pairs("abbceffg"));
         assert!(!has_2_pairs("abbcegjk"));
 
-        assert_eq!(&next_pass("abcdefgh"), "abcdffaa");
+        assert_eq!(next_pass("abcdefgh").unwrap(), "abcdffaa");
     }
 }
+
+crate::solve!(2015, 11, "Corporate Policy", p1, p2);
@@ -48,3 +48,5 @@ mod tests {
         assert_eq!(p2("[1,\"red\",5]"), 6);
     }
 }
+
+crate::solve!(2015, 12, "JSAbacusFramework.io", p1, p2);
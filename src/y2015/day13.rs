@@ -1,26 +1,32 @@
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::collections::BTreeMap;
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, digit1},
+    character::complete::alpha1,
     combinator::map,
-    combinator::map_res,
     sequence::{separated_pair, terminated, tuple},
     IResult,
 };
 
+use crate::{
+    graph::{held_karp, Objective, Tour},
+    parse::{parse_lines, uint},
+    Error,
+};
+
 type Name = String;
 type Happiness = isize;
 type Graph = BTreeMap<Name, BTreeMap<Name, Happiness>>;
 
-pub fn p1(input: &str) -> isize {
-    let graph = parse_graph(input);
+pub type Input<'a> = Graph;
+
+pub fn p1(graph: &Input) -> Result<isize, Error> {
     happiest_path(graph)
 }
 
-pub fn p2(input: &str) -> isize {
-    let mut graph = parse_graph(input);
+pub fn p2(graph: &Input) -> Result<isize, Error> {
+    let mut graph = graph.clone();
     let myself = "Myself";
 
     for edges in graph.values_mut() {
@@ -29,59 +35,31 @@ pub fn p2(input: &str) -> isize {
 
     graph.insert(myself.into(), graph.keys().map(|k| (k.into(), 0)).collect());
 
-    happiest_path(graph)
+    happiest_path(&graph)
 }
 
-fn happiest_path(graph: Graph) -> isize {
-    let mut result = isize::MIN;
-
-    let path_len = graph.len();
-
-    let start = graph.iter().next().unwrap().0;
-    let mut track = VecDeque::from([(vec![start], BTreeSet::from([start]), 0)]);
-
-    while let Some((path, visits, mut happiness)) = track.pop_front() {
-        let &node = path.last().unwrap();
-
-        if path.len() == path_len {
-            let &start = path.first().unwrap();
-            happiness += graph.get(start).unwrap().get(node).unwrap()
-                + graph.get(node).unwrap().get(start).unwrap();
-            if happiness > result {
-                result = happiness;
-                continue;
-            }
-        }
-
-        let edges = graph.get(node).unwrap();
-
-        for (edge_name, edge_cost) in edges {
-            if visits.contains(edge_name) {
-                continue;
-            }
-
-            let reversed_cost = graph.get(edge_name).unwrap().get(node).unwrap();
-
-            track.push_back((
-                path.iter().chain([&edge_name]).cloned().collect(),
-                visits.iter().chain([&edge_name]).cloned().collect(),
-                happiness + edge_cost + reversed_cost,
-            ));
-        }
-    }
-
-    result
+/// The total happiness of the best seating around the table, where both
+/// neighbours of each pair gain or lose happiness.
+fn happiest_path(graph: &Graph) -> Result<isize, Error> {
+    let names: Vec<_> = graph.keys().collect();
+    let happiness = |a: usize, b: usize| {
+        let (a, b) = (names[a], names[b]);
+        Some(graph.get(a)?.get(b)? + graph.get(b)?.get(a)?)
+    };
+
+    held_karp(names.len(), happiness, Objective::Max, Tour::Closed)
+        .map(|path| path.cost)
+        .ok_or_else(|| Error::unsolvable("nobody can sit next to each other"))
 }
 
-fn parse_graph(input: &str) -> Graph {
-    input
-        .trim()
-        .lines()
-        .fold(Default::default(), |mut graph, line| {
-            let (a, b, h) = parse_line(line).unwrap().1;
+fn parse_graph(input: &str) -> Result<Graph, Error> {
+    let rule = terminated(parse_line, tag("."));
+    Ok(parse_lines(input, rule)?
+        .into_iter()
+        .fold(Graph::new(), |mut graph, (a, b, h)| {
             graph.entry(a).or_default().insert(b, h);
             graph
-        })
+        }))
 }
 
 fn parse_line(input: &str) -> IResult<&str, (Name, Name, Happiness)> {
@@ -100,12 +78,11 @@ fn parse_name(input: &str) -> IResult<&str, Name> {
 }
 
 fn parse_happiness(input: &str) -> IResult<&str, Happiness> {
-    let parse_num = map_res(digit1, |n: &str| n.parse::<Happiness>());
     let parse_gain = map(tag("would gain "), |_| 1);
     let parse_lose = map(tag("would lose "), |_| -1);
 
     map(
-        tuple((alt((parse_gain, parse_lose)), parse_num)),
+        tuple((alt((parse_gain, parse_lose)), uint::<Happiness>)),
         |(sign, num)| sign * num,
     )(input)
 }
@@ -129,8 +106,8 @@ David would gain 41 happiness units by sitting next to Carol.";
 
     #[test]
     fn test_p1() {
-        assert_eq!(parse_graph(INPUT), expected_graph());
-        assert_eq!(p1(INPUT), 330);
+        assert_eq!(parse_graph(INPUT).unwrap(), expected_graph());
+        assert_eq!(p1(&expected_graph()).unwrap(), 330);
     }
 
     fn expected_graph() -> Graph {
@@ -170,3 +147,5 @@ David would gain 41 happiness units by sitting next to Carol.";
         ])
     }
 }
+
+crate::solve!(2015, 13, "Knights of the Dinner Table", parse_graph, p1, p2);
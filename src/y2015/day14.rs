@@ -1,32 +1,38 @@
 use nom::{
     bytes::complete::tag,
-    character::complete::{alpha1, digit1},
+    character::complete::alpha1,
     combinator::map,
-    sequence::{preceded, tuple},
+    sequence::{preceded, terminated, tuple},
     IResult,
 };
 
-pub fn p1(input: &str) -> usize {
-    run_race(input, 2503)
+use crate::{
+    parse::{parse_lines, uint},
+    Error,
+};
+
+pub type Input<'a> = Vec<Spec>;
+
+pub fn p1(specs: &Input) -> usize {
+    run_race(specs, 2503)
 }
 
-pub fn p2(input: &str) -> usize {
-    run_race_p2(input, 2503)
+pub fn p2(specs: &Input) -> usize {
+    run_race_p2(specs, 2503)
 }
 
 type Name = String;
 type Spec = (usize, usize, usize);
 
-fn run_race(input: &str, time: usize) -> usize {
-    parsed_specs(input)
-        .map(|spec| compute_distance(spec, time))
+fn run_race(specs: &[Spec], time: usize) -> usize {
+    specs
+        .iter()
+        .map(|&spec| compute_distance(spec, time))
         .max()
         .unwrap()
 }
 
-fn run_race_p2(input: &str, time: usize) -> usize {
-    let specs: Vec<_> = parsed_specs(input).collect();
-
+fn run_race_p2(specs: &[Spec], time: usize) -> usize {
     let mut scores = vec![0; specs.len()];
 
     for t in 1..=time {
@@ -47,8 +53,9 @@ fn run_race_p2(input: &str, time: usize) -> usize {
     scores.into_iter().max().unwrap()
 }
 
-fn parsed_specs(input: &str) -> impl Iterator<Item = Spec> + '_ {
-    input.trim().lines().map(|l| parse_line(l).unwrap().1 .1)
+fn parse_specs(input: &str) -> Result<Input<'_>, Error> {
+    let spec = map(parse_line, |(_, spec)| spec);
+    Ok(parse_lines(input, terminated(spec, tag(" seconds.")))?)
 }
 
 fn compute_distance((speed, run_time, rest_time): Spec, time: usize) -> usize {
@@ -68,16 +75,12 @@ fn parse_name(input: &str) -> IResult<&str, Name> {
 
 fn parse_spec(input: &str) -> IResult<&str, Spec> {
     tuple((
-        preceded(tag(" can fly "), parse_num),
-        preceded(tag(" km/s for "), parse_num),
-        preceded(tag(" seconds, but then must rest for "), parse_num),
+        preceded(tag(" can fly "), uint),
+        preceded(tag(" km/s for "), uint),
+        preceded(tag(" seconds, but then must rest for "), uint),
     ))(input)
 }
 
-fn parse_num(input: &str) -> IResult<&str, usize> {
-    map(digit1, |s: &str| s.parse::<usize>().unwrap())(input)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,11 +111,13 @@ Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds.
         assert_eq!(compute_distance((14, 10, 127), 138), 154);
 
         assert_eq!(compute_distance((16, 11, 162), 1000), 1056);
-        assert_eq!(run_race(INPUT, 1000), 1120);
+        assert_eq!(run_race(&parse_specs(INPUT).unwrap(), 1000), 1120);
     }
 
     #[test]
     fn test_p2() {
-        assert_eq!(run_race_p2(INPUT, 1000), 689);
+        assert_eq!(run_race_p2(&parse_specs(INPUT).unwrap(), 1000), 689);
     }
 }
+
+crate::solve!(2015, 14, "Reindeer Olympics", parse_specs, p1, p2);
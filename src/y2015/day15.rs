@@ -108,3 +108,5 @@ Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3";
         assert_eq!(p2(INPUT), 57600000);
     }
 }
+
+crate::solve!(2015, 15, "Science for Hungry People", p1, p2);
@@ -89,3 +89,5 @@ mod tests {
         );
     }
 }
+
+crate::solve!(2015, 16, "Aunt Sue", p1, p2);
@@ -69,3 +69,5 @@ mod tests {
         assert_eq!(resolve(25, &[20, 15, 10, 5, 5]).1, 3);
     }
 }
+
+crate::solve!(2015, 17, "No Such Thing as Too Much", p1, p2);
@@ -15,3 +15,23 @@ pub mod day14;
 pub mod day15;
 pub mod day16;
 pub mod day17;
+
+pub const DAYS: &[crate::Day] = &[
+    day01::DAY,
+    day02::DAY,
+    day03::DAY,
+    day04::DAY,
+    day05::DAY,
+    day06::DAY,
+    day07::DAY,
+    day08::DAY,
+    day09::DAY,
+    day10::DAY,
+    day11::DAY,
+    day12::DAY,
+    day13::DAY,
+    day14::DAY,
+    day15::DAY,
+    day16::DAY,
+    day17::DAY,
+];
@@ -1,21 +1,27 @@
-pub fn p1(input: &str) -> usize {
-    sort_elves(parse_elves(input))[0]
-}
+use crate::{
+    parse::{blocks, uint},
+    Error,
+};
+
+/// The calories carried by each elf, most first.
+pub type Input<'a> = Vec<usize>;
 
-fn parse_elves(input: &str) -> Vec<usize> {
-    input
-        .split_terminator("\n\n")
-        .map(|elf| elf.lines().map(|l| l.parse::<usize>().unwrap()).sum())
-        .collect()
+pub fn p1(elves: &Input) -> usize {
+    elves[0]
 }
 
-fn sort_elves(mut elves: Vec<usize>) -> Vec<usize> {
+fn parse_elves(input: &str) -> Result<Input<'_>, Error> {
+    let mut elves = blocks(input)
+        .iter()
+        .map(|elf| elf.iter().map(|l| l.parse(uint::<usize>)).sum())
+        .collect::<Result<Vec<_>, _>>()?;
     elves.sort_by_key(|&n| std::cmp::Reverse(n));
-    elves
+
+    Ok(elves)
 }
 
-pub fn p2(input: &str) -> usize {
-    sort_elves(parse_elves(input))[0..3].iter().sum()
+pub fn p2(elves: &Input) -> usize {
+    elves[0..3].iter().sum()
 }
 
 #[cfg(test)]
@@ -39,11 +45,13 @@ mod tests {
 
     #[test]
     fn test_p1() {
-        assert_eq!(p1(INPUT), 24000);
+        assert_eq!(p1(&parse_elves(INPUT).unwrap()), 24000);
     }
 
     #[test]
     fn test_p2() {
-        assert_eq!(p2(INPUT), 45000);
+        assert_eq!(p2(&parse_elves(INPUT).unwrap()), 45000);
     }
 }
+
+crate::solve!(2022, 1, "Calorie Counting", parse_elves, p1, p2);
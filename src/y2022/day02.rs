@@ -125,3 +125,5 @@ C Z";
         assert_eq!(p2(INPUT), 12);
     }
 }
+
+crate::solve!(2022, 2, "Rock Paper Scissors", p1, p2);
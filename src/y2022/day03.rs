@@ -39,7 +39,7 @@ pub fn p2(input: &str) -> usize {
 }
 
 fn score(c: char) -> usize {
-    if ('a'..='z').contains(&c) {
+    if c.is_ascii_lowercase() {
         c as usize - 'a' as usize + 1
     } else {
         c as usize - 'A' as usize + 27
@@ -67,3 +67,5 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
         assert_eq!(p2(INPUT), 70);
     }
 }
+
+crate::solve!(2022, 3, "Rucksack Reorganization", p1, p2);
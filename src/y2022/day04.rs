@@ -1,4 +1,4 @@
-use std::ops::RangeInclusive;
+use crate::ranges::RangeSet;
 
 pub fn p1(input: &str) -> usize {
     solve(input, either_fully_contains_other)
@@ -8,7 +8,7 @@ pub fn p2(input: &str) -> usize {
     solve(input, are_overlapping)
 }
 
-type Pair = RangeInclusive<usize>;
+type Pair = RangeSet;
 
 fn solve(input: &str, predicate: fn(&(Pair, Pair)) -> bool) -> usize {
     input
@@ -24,19 +24,16 @@ fn solve(input: &str, predicate: fn(&(Pair, Pair)) -> bool) -> usize {
 fn parse_range(s: &str) -> Pair {
     let (start, end) = s.split_once('-').unwrap();
 
-    (start.parse().unwrap())..=(end.parse().unwrap())
+    let (start, end): (usize, usize) = (start.parse().unwrap(), end.parse().unwrap());
+    RangeSet::from(start..end + 1)
 }
 
 fn either_fully_contains_other((l, r): &(Pair, Pair)) -> bool {
-    fully_contains(l, r) || fully_contains(r, l)
-}
-
-fn fully_contains(l: &Pair, r: &Pair) -> bool {
-    l.contains(r.start()) && l.contains(r.end())
+    l.is_subset(r) || r.is_subset(l)
 }
 
 fn are_overlapping((l, r): &(Pair, Pair)) -> bool {
-    l.contains(r.start()) || l.contains(r.end()) || r.contains(l.start()) || r.contains(l.end())
+    !l.intersection(r).is_empty()
 }
 
 #[cfg(test)]
@@ -60,3 +57,5 @@ mod tests {
         assert_eq!(p2(INPUT), 4);
     }
 }
+
+crate::solve!(2022, 4, "Camp Cleanup", p1, p2);
@@ -1,28 +1,37 @@
 use std::collections::VecDeque;
 
-pub fn p1(input: &str) -> String {
-    let (drawing, steps) = input.split_once("\n\n").unwrap();
-    let mut stacks = parse_drawing(drawing);
+use nom::{
+    bytes::complete::tag,
+    character::complete::u32,
+    combinator::{all_consuming, map},
+    sequence::{preceded, tuple},
+};
 
-    for (mut column, source, dest) in parse_steps(steps) {
+use crate::{Error, ParseError};
+
+pub type Input<'a> = (Vec<VecDeque<char>>, Vec<Step>);
+
+pub fn p1((stacks, steps): &Input) -> Result<String, Error> {
+    let mut stacks = stacks.clone();
+
+    for &(mut column, source, dest) in steps {
         while column > 0 {
-            let c = stacks[source - 1].pop_front().unwrap();
+            let c = take(&mut stacks, source)?;
             stacks[dest - 1].push_front(c);
             column -= 1;
         }
     }
 
-    stacks.iter().map(|col| col[0]).collect()
+    Ok(tops(&stacks))
 }
 
-pub fn p2(input: &str) -> String {
-    let (drawing, steps) = input.split_once("\n\n").unwrap();
-    let mut stacks = parse_drawing(drawing);
+pub fn p2((stacks, steps): &Input) -> Result<String, Error> {
+    let mut stacks = stacks.clone();
 
-    for (mut column, source, dest) in parse_steps(steps) {
+    for &(mut column, source, dest) in steps {
         let mut acc = VecDeque::with_capacity(column);
         while column > 0 {
-            let c = stacks[source - 1].pop_front().unwrap();
+            let c = take(&mut stacks, source)?;
             acc.push_back(c);
             column -= 1;
         }
@@ -30,56 +39,94 @@ pub fn p2(input: &str) -> String {
         stacks[dest - 1].rotate_right(acc.len());
     }
 
-    stacks.iter().map(|col| col[0]).collect()
+    Ok(tops(&stacks))
+}
+
+/// Takes the top crate off the 1-based stack `index`.
+fn take(stacks: &mut [VecDeque<char>], index: usize) -> Result<char, Error> {
+    stacks[index - 1]
+        .pop_front()
+        .ok_or_else(|| Error::unsolvable(format!("stack {index} is empty")))
+}
+
+fn tops(stacks: &[VecDeque<char>]) -> String {
+    stacks.iter().filter_map(VecDeque::front).collect()
 }
 
 type Step = (usize, usize, usize);
 
-fn parse_drawing(drawing: &str) -> Vec<VecDeque<char>> {
-    let mut stacks = Vec::new();
-    let drawing_height = drawing.lines().count() - 1;
+fn parse_input(input: &str) -> Result<Input<'_>, Error> {
+    let Some((drawing, steps)) = input.split_once("\n\n") else {
+        let (i, last) = input.lines().enumerate().last().unwrap_or((0, ""));
+        return Err(ParseError::at(i, last, "", "expected a blank line after the drawing").into());
+    };
 
-    for (_, line) in drawing
-        .lines()
-        .enumerate()
-        .take_while(|(i, _)| *i < drawing_height)
-    {
-        let mut peek = line.chars();
-        let mut stack_index = 0;
-
-        while peek.next().is_some() {
-            if stack_index >= stacks.len() {
-                stacks.push(VecDeque::new());
-            }
-            if let Some(c) = peek.next() {
-                if c.is_alphabetic() {
-                    stacks[stack_index].push_back(c);
+    let stacks = parse_drawing(drawing)?;
+    let steps = parse_steps(steps, drawing.lines().count() + 1, stacks.len())?;
+
+    Ok((stacks, steps))
+}
+
+fn parse_drawing(drawing: &str) -> Result<Vec<VecDeque<char>>, ParseError> {
+    let lines: Vec<_> = drawing.lines().collect();
+    let Some((labels, rows)) = lines.split_last() else {
+        return Err(ParseError::at(
+            0,
+            "",
+            "",
+            "expected a drawing of the stacks",
+        ));
+    };
+    let mut stacks = vec![VecDeque::new(); labels.split_whitespace().count()];
+
+    for (i, line) in rows.iter().enumerate() {
+        for (stack_index, start) in (0..line.len()).step_by(4).enumerate() {
+            let cell = line.get(start..line.len().min(start + 3)).unwrap_or("");
+            match cell.as_bytes() {
+                blank if blank.iter().all(|&b| b == b' ') => {}
+                _ if stack_index >= stacks.len() => {
+                    return Err(ParseError::at(i, line, cell, "crate outside of the stacks"))
+                }
+                [b'[', c, b']'] if c.is_ascii_alphabetic() => {
+                    stacks[stack_index].push_back(*c as char)
                 }
+                _ => return Err(ParseError::at(i, line, cell, "expected a crate like `[A]`")),
             }
-            peek.next();
-            peek.next();
-
-            stack_index += 1;
         }
     }
 
-    stacks
+    Ok(stacks)
 }
 
-fn parse_steps(input: &str) -> impl Iterator<Item = Step> + '_ {
-    input.lines().map(|l| {
-        let (_move, rest) = l.split_once(' ').unwrap();
-        let (col, rest) = rest.split_once(' ').unwrap();
-        let (_from, rest) = rest.split_once(' ').unwrap();
-        let (source, rest) = rest.split_once(' ').unwrap();
-        let (_to, dest) = rest.split_once(' ').unwrap();
-
-        (
-            col.parse().unwrap(),
-            source.parse().unwrap(),
-            dest.parse().unwrap(),
-        )
-    })
+/// Parses the steps that start on the 0-based line `offset` of the input.
+fn parse_steps(input: &str, offset: usize, stack_count: usize) -> Result<Vec<Step>, ParseError> {
+    let num = || map(u32, |n| n as usize);
+    let mut parse_step = all_consuming(tuple((
+        preceded(tag("move "), num()),
+        preceded(tag(" from "), num()),
+        preceded(tag(" to "), num()),
+    )));
+
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let (_, step) = parse_step(l).map_err(|e| ParseError::nom(offset + i, l, e))?;
+            if let Some(n) = [step.1, step.2]
+                .into_iter()
+                .find(|n| !(1..=stack_count).contains(n))
+            {
+                return Err(ParseError::at(
+                    offset + i,
+                    l,
+                    l,
+                    format!("there is no stack {n}"),
+                ));
+            }
+
+            Ok(step)
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -106,7 +153,7 @@ move 1 from 1 to 2";
  1   2   3 
 ";
         assert_eq!(
-            parse_drawing(drawing),
+            parse_drawing(drawing).unwrap(),
             vec![
                 VecDeque::from(['N', 'Z']),
                 VecDeque::from(['D', 'C', 'M']),
@@ -115,13 +162,50 @@ move 1 from 1 to 2";
         );
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let Err(Error::Parse(e)) = parse_input("[A] (B)\n 1   2 \n\nmove 1 from 1 to 2") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (1, 5));
+
+        let Err(Error::Parse(e)) = parse_input("[A] [B]\n 1   2 \n\nmove 1 from 1 to 3") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.reason.as_str()), (4, "there is no stack 3"));
+
+        let Err(Error::Parse(e)) = parse_input("[A] [B]\n 1   2 \n\nmove one from 1 to 2") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (4, 6));
+
+        let Err(Error::Parse(e)) = parse_input("\n\nmove 1 from 1 to 2") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(e.reason, "expected a drawing of the stacks");
+
+        let Err(Error::Parse(e)) = parse_input("[A] [B]\n 1 \n\nmove 1 from 1 to 1") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (1, 5));
+    }
+
     #[test]
     fn test_p1() {
-        assert_eq!(p1(INPUT), "CMZ");
+        assert_eq!(p1(&parse_input(INPUT).unwrap()).unwrap(), "CMZ");
+        assert!(matches!(
+            p1(&parse_input("[A]    \n 1   2 \n\nmove 2 from 1 to 2").unwrap()),
+            Err(Error::Unsolvable(_))
+        ));
     }
 
     #[test]
     fn test_p2() {
-        assert_eq!(p2(INPUT), "MCD");
+        assert_eq!(p2(&parse_input(INPUT).unwrap()).unwrap(), "MCD");
+        // the rightmost stack starts out empty
+        let input = parse_input("[A]\n 1   2 \n\nmove 1 from 1 to 2").unwrap();
+        assert_eq!(p2(&input).unwrap(), "A");
     }
 }
+
+crate::solve!(2022, 5, "Supply Stacks", parse_input, p1, p2);
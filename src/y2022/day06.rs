@@ -47,3 +47,5 @@ mod tests {
         }
     }
 }
+
+crate::solve!(2022, 6, "Tuning Trouble", p1, p2);
@@ -86,3 +86,5 @@ $ ls
         assert_eq!(p2(INPUT), 24933642);
     }
 }
+
+crate::solve!(2022, 7, "No Space Left On Device", p1, p2);
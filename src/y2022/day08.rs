@@ -1,87 +1,51 @@
-use std::iter::repeat;
+use crate::{
+    grid::{Grid, Pos, DIRS4},
+    Error,
+};
 
-pub fn p1(input: &str) -> usize {
-    let grid = parse_grid(input);
+pub type Input<'a> = Grid<u32>;
 
-    let mut count = (grid.h + grid.w - 2) * 2;
-    for (x, y) in grid.iter() {
-        if grid.can_see(x, y) {
-            count += 1;
-        }
-    }
-
-    count
+pub fn p1(grid: &Grid<u32>) -> usize {
+    grid.positions().filter(|&pos| can_see(grid, pos)).count()
 }
 
-pub fn p2(input: &str) -> usize {
-    let grid = parse_grid(input);
-
-    let mut score = 0;
-    for (x, y) in grid.iter() {
-        score = score.max(grid.scenic_score(x, y));
-    }
-
-    score
+pub fn p2(grid: &Grid<u32>) -> usize {
+    grid.positions()
+        .map(|pos| scenic_score(grid, pos))
+        .max()
+        .unwrap_or(0)
 }
 
-struct Grid {
-    data: Vec<usize>,
-    h: usize,
-    w: usize,
-}
-
-impl Grid {
-    fn at(&self, x: usize, y: usize) -> usize {
-        self.data[x + y * self.w]
-    }
-
-    fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
-        (1..(self.h - 1)).flat_map(|y| (1..(self.w - 1)).zip(repeat(y)))
-    }
-
-    fn can_see(&self, x: usize, y: usize) -> bool {
-        let n = self.at(x, y);
+/// Whether the tree at `pos` can be seen from outside the grid, which is
+/// always the case on the edges.
+fn can_see(grid: &Grid<u32>, pos: Pos) -> bool {
+    let n = grid[pos];
 
-        (0..y).all(|i| self.at(x, i) < n)
-            || ((y + 1)..self.h).all(|i| self.at(x, i) < n)
-            || (0..x).all(|i| self.at(i, y) < n)
-            || ((x + 1)..self.w).all(|i| self.at(i, y) < n)
-    }
+    DIRS4
+        .into_iter()
+        .any(|dir| grid.ray(pos, dir).all(|p| grid[p] < n))
+}
 
-    fn range_score(&self, base: usize, coords: impl Iterator<Item = (usize, usize)>) -> usize {
-        let mut score = 0;
-        for (dx, dy) in coords {
-            score += 1;
-            if self.at(dx, dy) >= base {
-                break;
+fn scenic_score(grid: &Grid<u32>, pos: Pos) -> usize {
+    let base = grid[pos];
+
+    DIRS4
+        .into_iter()
+        .map(|dir| {
+            let mut score = 0;
+            for p in grid.ray(pos, dir) {
+                score += 1;
+                if grid[p] >= base {
+                    break;
+                }
             }
-        }
-        score
-    }
-
-    fn scenic_score(&self, x: usize, y: usize) -> usize {
-        let base = self.at(x, y);
-
-        self.range_score(base, (0..x).rev().zip(repeat(y)))
-            * self.range_score(base, ((x + 1)..self.w).zip(repeat(y)))
-            * self.range_score(base, (repeat(x)).zip((0..y).rev()))
-            * self.range_score(base, (repeat(x)).zip((y + 1)..self.h))
-    }
+            score
+        })
+        .product()
 }
 
-fn parse_grid(input: &str) -> Grid {
-    let h = input.lines().count();
-    let w = input.lines().next().unwrap().len();
-
-    let mut data = Vec::with_capacity(h * w);
-
-    for l in input.lines() {
-        for c in l.chars() {
-            data.push(c.to_digit(10).unwrap() as usize);
-        }
-    }
-
-    Grid { data, h, w }
+fn parse_grid(input: &str) -> Result<Grid<u32>, Error> {
+    Ok(Grid::parse(input, |c| c.to_digit(10))?)
 }
 
 #[cfg(test)]
@@ -97,11 +61,13 @@ mod tests {
 
     #[test]
     fn test_p1() {
-        assert_eq!(p1(INPUT), 21);
+        assert_eq!(p1(&parse_grid(INPUT).unwrap()), 21);
     }
 
     #[test]
     fn test_p2() {
-        assert_eq!(p2(INPUT), 8);
+        assert_eq!(p2(&parse_grid(INPUT).unwrap()), 8);
     }
 }
+
+crate::solve!(2022, 8, "Treetop Tree House", parse_grid, p1, p2);
@@ -111,4 +111,4 @@ eightwothreeeight";
     }
 }
 
-aoc::solve!(2023, 1, p1, p2);
+crate::solve!(2023, 1, "Trebuchet?!", p1, p2);
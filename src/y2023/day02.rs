@@ -78,4 +78,4 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
     }
 }
 
-aoc::solve!(2023, 2, p1, p2);
+crate::solve!(2023, 2, "Cube Conundrum", p1, p2);
@@ -1,32 +1,36 @@
-use std::{collections::HashMap, ops::Range};
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Range,
+};
 
-pub fn p1(input: &str) -> usize {
-    let schema = Schema::parse(input);
+use crate::{
+    grid::{Grid, Pos},
+    Error,
+};
 
-    schema
-        .get_num_coords()
+pub type Input<'a> = Grid<char>;
+
+pub fn p1(schema: &Grid<char>) -> usize {
+    get_num_coords(schema)
         .into_iter()
         .filter(|coord| {
-            schema
-                .get_neighbors(coord)
-                .any(|(ny, nx)| is_symbol(schema.data[ny][nx]))
+            get_neighbors(schema, coord)
+                .into_iter()
+                .any(|pos| is_symbol(schema[pos]))
         })
-        .map(|coords| schema.parse_number(&coords))
+        .map(|coords| parse_number(schema, &coords))
         .sum()
 }
 
-type GearMap = HashMap<(usize, usize), Vec<Coords>>;
-
-pub fn p2(input: &str) -> usize {
-    let schema = Schema::parse(input);
+type GearMap = HashMap<Pos, Vec<Coords>>;
 
-    schema
-        .get_num_coords()
+pub fn p2(schema: &Grid<char>) -> usize {
+    get_num_coords(schema)
         .into_iter()
         .fold(GearMap::new(), |mut acc, coord| {
-            for neighbor in schema
-                .get_neighbors(&coord)
-                .filter(|&(ny, nx)| schema.data[ny][nx] == '*')
+            for neighbor in get_neighbors(schema, &coord)
+                .into_iter()
+                .filter(|&pos| schema[pos] == '*')
             {
                 acc.entry(neighbor).or_default().push(coord.clone());
             }
@@ -35,94 +39,53 @@ pub fn p2(input: &str) -> usize {
         })
         .iter()
         .filter(|(_, v)| v.len() == 2)
-        .map(|(_, coords)| schema.parse_number(&coords[0]) * schema.parse_number(&coords[1]))
+        .map(|(_, coords)| parse_number(schema, &coords[0]) * parse_number(schema, &coords[1]))
         .sum()
 }
 
+/// A number as its row and the columns it spans.
 type Coords<T = usize> = (T, Range<T>);
 
-struct Schema {
-    data: Vec<Vec<char>>,
+fn parse_schema(input: &str) -> Result<Grid<char>, Error> {
+    Ok(Grid::parse(input.trim(), Some)?)
 }
 
-impl Schema {
-    fn parse(input: &str) -> Self {
-        Self {
-            data: input.trim().lines().map(|l| l.chars().collect()).collect(),
-        }
-    }
+fn parse_number(schema: &Grid<char>, (y, xrange): &Coords) -> usize {
+    schema.row(*y)[xrange.start..xrange.end]
+        .iter()
+        .collect::<String>()
+        .parse::<usize>()
+        .expect("Unable to parse number")
+}
 
-    fn parse_number(&self, (y, xrange): &Coords) -> usize {
-        self.data[*y][xrange.start..xrange.end]
-            .iter()
-            .collect::<String>()
-            .parse::<usize>()
-            .expect("Unable to parse number")
-    }
+fn get_num_coords(schema: &Grid<char>) -> Vec<Coords> {
+    let mut coords: Vec<Coords> = Vec::new();
+    for (y, row) in schema.rows().enumerate() {
+        let mut currently_digit = false;
 
-    fn get_num_coords(&self) -> Vec<(usize, Range<usize>)> {
-        let mut coords: Vec<Coords> = Vec::new();
-        for (y, row) in self.data.iter().enumerate() {
-            let mut currently_digit = false;
-
-            for x in 0..row.len() {
-                if is_digit(row[x]) {
-                    if !currently_digit {
-                        coords.push((y, x..row.len()));
-                        currently_digit = true;
-                    }
-                } else if currently_digit {
-                    currently_digit = false;
-                    let coord = coords.last_mut().expect("Coords can't be empty");
-                    coord.1.end = x;
+        for x in 0..row.len() {
+            if is_digit(row[x]) {
+                if !currently_digit {
+                    coords.push((y, x..row.len()));
+                    currently_digit = true;
                 }
+            } else if currently_digit {
+                currently_digit = false;
+                let coord = coords.last_mut().expect("Coords can't be empty");
+                coord.1.end = x;
             }
         }
-        coords
-    }
-
-    fn get_neighbors(&self, (y, xrange): &Coords) -> impl Iterator<Item = (usize, usize)> {
-        let mut result = Vec::with_capacity(xrange.len() * 2 + 6);
-
-        let x0 = if xrange.start > 0 {
-            xrange.start - 1
-        } else {
-            xrange.start
-        };
-        // xrange.end is exclusive, just like row_len
-        let x1 = if xrange.end < self.row_len() {
-            xrange.end + 1
-        } else {
-            xrange.end
-        };
-
-        // top
-        if *y > 0 {
-            for x in x0..x1 {
-                result.push((y - 1, x));
-            }
-        }
-        // left
-        if xrange.start > 0 {
-            result.push((*y, xrange.start - 1));
-        }
-        // right
-        if xrange.end < self.row_len() {
-            result.push((*y, xrange.end));
-        }
-        // bottom
-        if *y + 1 < self.data.len() {
-            for x in x0..x1 {
-                result.push((y + 1, x));
-            }
-        }
-
-        result.into_iter()
     }
+    coords
+}
 
-    fn row_len(&self) -> usize {
-        self.data.first().map(|row| row.len()).unwrap_or(0)
-    }
+/// The cells around a number, each once.
+fn get_neighbors(schema: &Grid<char>, (y, xrange): &Coords) -> BTreeSet<Pos> {
+    xrange
+        .clone()
+        .flat_map(|x| schema.neighbours8((x, *y)))
+        .filter(|&(nx, ny)| ny != *y || !xrange.contains(&nx))
+        .collect()
 }
 
 fn is_symbol(cell: char) -> bool {
@@ -150,12 +113,12 @@ mod tests {
 
     #[test]
     fn test_p1() {
-        assert_eq!(p1(INPUT), 4361);
+        assert_eq!(p1(&parse_schema(INPUT).unwrap()), 4361);
     }
 
     #[test]
     fn test_p2() {
-        assert_eq!(p2(INPUT), 467835);
+        assert_eq!(p2(&parse_schema(INPUT).unwrap()), 467835);
     }
 
     #[test]
@@ -173,4 +136,4 @@ mod tests {
     }
 }
 
-aoc::solve!(2023, 3, p1, p2);
+crate::solve!(2023, 3, "Gear Ratios", parse_schema, p1, p2);
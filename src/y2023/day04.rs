@@ -1,10 +1,25 @@
 use std::collections::{BTreeSet, VecDeque};
 
-pub fn p1(input: &str) -> usize {
-    input
-        .trim()
-        .lines()
-        .map(parse_line_wins)
+use nom::{
+    bytes::complete::tag,
+    character::complete::space1,
+    sequence::{preceded, separated_pair, tuple},
+};
+
+use crate::{
+    parse::{nums, parse_lines, uint},
+    Error,
+};
+
+/// The winning numbers and the numbers we have, of each card.
+pub type Input<'a> = Vec<Card>;
+
+type Card = (Vec<usize>, Vec<usize>);
+
+pub fn p1(cards: &Input) -> usize {
+    cards
+        .iter()
+        .map(count_wins)
         .map(|wins| {
             if wins > 0 {
                 2usize.pow(wins as u32 - 1)
@@ -15,11 +30,11 @@ pub fn p1(input: &str) -> usize {
         .sum()
 }
 
-pub fn p2(input: &str) -> usize {
+pub fn p2(cards: &Input) -> usize {
     let mut acc = VecDeque::new();
     let mut total = 0;
 
-    for wins in input.trim().lines().map(parse_line_wins) {
+    for wins in cards.iter().map(count_wins) {
         let multiplier = acc.pop_front().unwrap_or(1);
 
         total += multiplier;
@@ -36,20 +51,16 @@ pub fn p2(input: &str) -> usize {
     total
 }
 
-fn parse_line_wins(l: &str) -> usize {
-    let (good_nums, my_nums) = l.split_once(": ").unwrap().1.split_once(" | ").unwrap();
-
-    let good_nums: BTreeSet<usize> = parse_nums(good_nums).collect();
-    parse_nums(my_nums)
-        .filter(|n| good_nums.contains(n))
-        .count()
+fn count_wins((good_nums, my_nums): &Card) -> usize {
+    let good_nums: BTreeSet<usize> = good_nums.iter().copied().collect();
+    my_nums.iter().filter(|n| good_nums.contains(n)).count()
 }
 
-fn parse_nums(input: &str) -> impl Iterator<Item = usize> + '_ {
-    input
-        .trim()
-        .split_ascii_whitespace()
-        .map(|s| s.parse::<usize>().unwrap())
+fn parse_cards(input: &str) -> Result<Input<'_>, Error> {
+    let header = tuple((tag("Card"), space1, uint::<usize>, tag(":")));
+    let card = preceded(header, separated_pair(nums, tag("|"), nums));
+
+    Ok(parse_lines(input, card)?)
 }
 
 #[cfg(test)]
@@ -66,13 +77,13 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 
     #[test]
     fn test_p1() {
-        assert_eq!(p1(INPUT), 13);
+        assert_eq!(p1(&parse_cards(INPUT).unwrap()), 13);
     }
 
     #[test]
     fn test_p2() {
-        assert_eq!(p2(INPUT), 30);
+        assert_eq!(p2(&parse_cards(INPUT).unwrap()), 30);
     }
 }
 
-aoc::solve!(2023, 4, p1, p2);
+crate::solve!(2023, 4, "Scratchcards", parse_cards, p1, p2);
@@ -1,85 +1,84 @@
-use std::{ops::Range, str::Lines};
+use std::ops::Range;
 
-pub fn p1(input: &str) -> usize {
-    let mut lines = input.trim().lines();
-    let seeds = parse_nums(lines.next().unwrap().split_once(' ').unwrap().1);
-    let maps = parse_maps(lines);
+use nom::{bytes::complete::tag, sequence::preceded};
 
-    seeds
-        .map(|seed| maps.iter().fold(seed, |acc, map| remap(acc, map)))
-        .min()
-        .unwrap()
-}
+use crate::{
+    parse::{blocks, nums, Line, Section},
+    ranges::RangeSet,
+    Error, ParseError,
+};
 
-pub fn p2(input: &str) -> usize {
-    let mut lines = input.trim().lines();
-    let seeds: Vec<_> = parse_seeds(&mut lines).collect();
-    let seed_pairs = seeds
+pub type Input<'a> = (Vec<usize>, Vec<Vec<RangePair>>);
+
+pub fn p1((seeds, maps): &Input) -> Result<usize, Error> {
+    seeds
         .iter()
-        .zip(seeds.iter().skip(1))
-        .enumerate()
-        .filter_map(|(i, pair)| if i % 2 == 0 { Some(pair) } else { None });
-    let maps = parse_maps(lines);
-
-    seed_pairs
-        .flat_map(|(&start, &len)| {
-            let end = start + len;
-            (start..end).map(|seed| maps.iter().fold(seed, |acc, map| remap(acc, map)))
-        })
+        .map(|&seed| maps.iter().fold(seed, |acc, map| remap(acc, map)))
         .min()
-        .unwrap()
+        .ok_or_else(|| Error::unsolvable("there are no seeds"))
 }
 
-fn parse_seeds<'a>(lines: &mut Lines<'a>) -> impl Iterator<Item = usize> + 'a {
-    parse_nums(lines.next().unwrap().split_once(' ').unwrap().1)
-}
+pub fn p2((seeds, maps): &Input) -> Result<usize, Error> {
+    let seeds: RangeSet = seeds
+        .chunks(2)
+        .map(|pair| pair[0]..pair[0] + pair[1])
+        .collect();
 
-fn parse_nums(input: &str) -> impl Iterator<Item = usize> + '_ {
-    input.split(' ').map(|s| s.parse::<usize>().unwrap())
+    maps.iter()
+        .fold(seeds, |acc, map| remap_ranges(&acc, map))
+        .min()
+        .ok_or_else(|| Error::unsolvable("there are no seeds"))
 }
 
 type RangePair = (Range<usize>, Range<usize>);
 
-fn parse_maps(lines: Lines) -> Vec<Vec<RangePair>> {
-    let (seed_map, lines) = parse_map(lines);
-    let (soil_map, lines) = parse_map(lines);
-    let (fertilizer_map, lines) = parse_map(lines);
-    let (water_map, lines) = parse_map(lines);
-    let (light_map, lines) = parse_map(lines);
-    let (temperature_map, lines) = parse_map(lines);
-    let (humidity_map, lines) = parse_map(lines);
-    let (location_map, _) = parse_map(lines);
-
-    vec![
-        seed_map,
-        soil_map,
-        fertilizer_map,
-        water_map,
-        light_map,
-        temperature_map,
-        humidity_map,
-        location_map,
-    ]
-}
+fn parse_almanac(input: &str) -> Result<Input<'_>, Error> {
+    let blocks = blocks(input);
+    let Some((header, maps)) = blocks.split_first() else {
+        return Err(ParseError::new(1, 1, "", "expected `seeds:`").into());
+    };
+    if let Some(extra) = header.get(1) {
+        return Err(extra
+            .error(extra.text, "expected a blank line after the seeds")
+            .into());
+    }
 
-fn parse_map<'a, Input: Iterator<Item = &'a str>>(
-    lines: Input,
-) -> (Vec<RangePair>, impl Iterator<Item = &'a str>) {
-    let mut rest = lines.skip_while(|l| l.trim().is_empty() || l.contains("map"));
-    let mut result = Vec::new();
-    for l in rest.by_ref() {
-        if l.trim().is_empty() {
-            break;
+    let seeds = header[0].parse(preceded(tag("seeds:"), nums::<usize>))?;
+    if seeds.len() % 2 != 0 {
+        return Err(header[0].error("", "expected pairs of seed ranges").into());
+    }
+    let mut fields = header[0].text.split_whitespace().skip(1).step_by(2);
+    for pair in seeds.chunks(2) {
+        let field = fields.next().unwrap_or("");
+        if pair[0].checked_add(pair[1]).is_none() {
+            return Err(header[0]
+                .error(field, "seed range past the largest number")
+                .into());
         }
+    }
+
+    let maps = maps
+        .iter()
+        .map(|block| Section::of(block)?.lines.iter().map(parse_entry).collect())
+        .collect::<Result<_, ParseError>>()?;
+
+    Ok((seeds, maps))
+}
 
-        let mut nums = parse_nums(l);
-        let dest = nums.next().unwrap();
-        let src = nums.next().unwrap();
-        let len = nums.next().unwrap();
-        result.push((dest..(dest + len), src..(src + len)));
+fn parse_entry(line: &Line) -> Result<RangePair, ParseError> {
+    let [dest, src, len] = line.parse(nums::<usize>)?[..] else {
+        return Err(line.error(line.text, "expected destination, source and length"));
+    };
+
+    let len_field = line.text.split_whitespace().nth(2).unwrap_or("");
+    let overflow = || line.error(len_field, "range past the largest number");
+    let dest_end = dest.checked_add(len).ok_or_else(overflow)?;
+    let src_end = src.checked_add(len).ok_or_else(overflow)?;
+    if offset(dest, src).is_none() {
+        return Err(line.error(line.text, "source too far from the destination"));
     }
 
-    (result, rest)
+    Ok((dest..dest_end, src..src_end))
 }
 
 fn remap(value: usize, map: &[RangePair]) -> usize {
@@ -93,6 +92,27 @@ fn remap(value: usize, map: &[RangePair]) -> usize {
     value
 }
 
+/// Maps whole ranges at once, splitting them where they straddle the
+/// boundaries of the entries of `map`.
+fn remap_ranges(values: &RangeSet, map: &[RangePair]) -> RangeSet {
+    let mut unmapped = values.clone();
+    let mut mapped = RangeSet::new();
+
+    for (dest, src) in map {
+        let offset = offset(dest.start, src.start).expect("offsets are checked when parsing");
+        let src = RangeSet::from(src.clone());
+        mapped = mapped.union(&unmapped.intersection(&src).shift(offset));
+        unmapped = unmapped.difference(&src);
+    }
+
+    mapped.union(&unmapped)
+}
+
+/// How far `src` is shifted to land on `dest`, if that fits in an `isize`.
+fn offset(dest: usize, src: usize) -> Option<isize> {
+    isize::try_from(dest as i128 - src as i128).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,15 +151,59 @@ humidity-to-location map:
 60 56 37
 56 93 4";
 
+    #[test]
+    fn test_parse_almanac() {
+        let Err(Error::Parse(e)) = parse_almanac("seeds: 79 14\n\nseed-to-soil map:\n50 98 x")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (4, 7));
+
+        let Err(Error::Parse(e)) = parse_almanac("seeds: 79 14\n\n50 98 2") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            (e.line, e.reason.as_str()),
+            (3, "expected a `label:` header")
+        );
+
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n18446744073709551615 1 5";
+        let Err(Error::Parse(e)) = parse_almanac(input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (4, 24));
+        assert_eq!(e.reason, "range past the largest number");
+
+        let Err(Error::Parse(e)) = parse_almanac("seeds: 1 2 18446744073709551615 1") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (1, 12));
+        assert_eq!(e.reason, "seed range past the largest number");
+
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n18446744073709551615 0 0";
+        let Err(Error::Parse(e)) = parse_almanac(input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (5, 1));
+        assert_eq!(e.reason, "source too far from the destination");
+    }
+
     #[test]
     fn test_p1() {
-        assert_eq!(p1(INPUT), 35);
+        assert_eq!(p1(&parse_almanac(INPUT).unwrap()).unwrap(), 35);
     }
 
     #[test]
     fn test_p2() {
-        assert_eq!(p2(INPUT), 46);
+        assert_eq!(p2(&parse_almanac(INPUT).unwrap()).unwrap(), 46);
     }
 }
 
-aoc::solve!(2023, 5, p1, p2);
+crate::solve!(
+    2023,
+    5,
+    "If You Give A Seed A Fertilizer",
+    parse_almanac,
+    p1,
+    p2
+);
@@ -1,37 +1,62 @@
 use std::iter::zip;
 
-pub fn p1(input: &str) -> usize {
-    parse_input(input)
+use nom::{bytes::complete::tag, sequence::preceded};
+
+use crate::{
+    parse::{lines, nums, Line},
+    Error, ParseError,
+};
+
+pub type Input<'a> = Races;
+
+/// The times and the record distances of the races, and of the one race they
+/// make once the spaces between the numbers are ignored.
+pub struct Races {
+    times: Vec<usize>,
+    distances: Vec<usize>,
+    time: usize,
+    distance: usize,
+}
+
+pub fn p1(input: &Races) -> usize {
+    races(&input.times, &input.distances)
         .map(|(t, d)| compute_ways(t, d))
         .product()
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
-    let mut lines = input.trim().lines();
-    let times = parse_nums(lines.next().unwrap());
-    let distances = parse_nums(lines.next().unwrap());
+pub fn p2(input: &Races) -> usize {
+    compute_ways(input.time, input.distance)
+}
 
-    zip(times, distances)
+fn races<'a>(
+    times: &'a [usize],
+    distances: &'a [usize],
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    zip(times.iter().copied(), distances.iter().copied())
 }
 
-pub fn p2(input: &str) -> usize {
-    let mut lines = input.trim().lines();
-    let parse_num = |l: &str| {
-        l.chars()
-            .skip_while(|c| !c.is_ascii_digit())
-            .filter(|c| c.is_ascii_digit())
-            .fold(0, |acc, c| acc * 10 + (c as u8 - b'0') as usize)
-    };
-    let time = parse_num(lines.next().unwrap());
-    let distance = parse_num(lines.next().unwrap());
+/// Reads the numbers after `label` as one, ignoring the spaces between them.
+fn concat(line: Line, label: &str) -> Result<usize, ParseError> {
+    let numbers = line.text.strip_prefix(label).unwrap_or(line.text);
+    let digits: String = numbers.split_whitespace().collect();
 
-    compute_ways(time, distance)
+    digits
+        .parse()
+        .map_err(|_| line.error(numbers.trim_start(), "expected a number that fits"))
 }
 
-fn parse_nums(l: &str) -> impl Iterator<Item = usize> + '_ {
-    l.split_whitespace()
-        .skip(1)
-        .map(|n| n.parse::<usize>().unwrap())
+fn parse_races(input: &str) -> Result<Input<'_>, Error> {
+    let lines: Vec<_> = lines(input.trim()).collect();
+    let [time, distance] = lines[..] else {
+        return Err(ParseError::new(1, 1, "", "expected a `Time:` and a `Distance:` line").into());
+    };
+
+    Ok(Races {
+        times: time.parse(preceded(tag("Time:"), nums))?,
+        distances: distance.parse(preceded(tag("Distance:"), nums))?,
+        time: concat(time, "Time:")?,
+        distance: concat(distance, "Distance:")?,
+    })
 }
 
 fn compute_ways(time: usize, distance: usize) -> usize {
@@ -56,18 +81,27 @@ Distance:  9  40  200";
 
     #[test]
     fn test_p1() {
-        assert_eq!(p1(INPUT), 288);
+        assert_eq!(p1(&parse_races(INPUT).unwrap()), 288);
     }
 
     #[test]
     fn test_p2() {
-        assert_eq!(p2(INPUT), 71503);
+        assert_eq!(p2(&parse_races(INPUT).unwrap()), 71503);
+        // leading zeros are digits like any other
+        assert_eq!(parse_races("Time: 1 05\nDistance: 2 0").unwrap().time, 105);
+
+        let input = "Time: 7\nDistance: 99999999999 99999999999";
+        let Err(Error::Parse(e)) = parse_races(input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line, e.column), (2, 11));
     }
 
     #[test]
     fn test_compute_ways() {
+        let input = parse_races(INPUT).unwrap();
         assert_eq!(
-            parse_input(INPUT)
+            races(&input.times, &input.distances)
                 .map(|(t, d)| compute_ways(t, d))
                 .collect::<Vec<_>>(),
             vec![4, 8, 9]
@@ -75,4 +109,4 @@ Distance:  9  40  200";
     }
 }
 
-aoc::solve!(2023, 6, p1, p2);
+crate::solve!(2023, 6, "Wait For It", parse_races, p1, p2);
@@ -120,4 +120,4 @@ QQQJA 483";
     }
 }
 
-aoc::solve!(2023, 7, p1, p2);
+crate::solve!(2023, 7, "Camel Cards", p1, p2);
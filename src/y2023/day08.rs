@@ -1,67 +1,212 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
-pub fn p1(input: &str) -> usize {
-    let (directions, map) = parse_input(input);
-    count_steps(directions, "AAA", &map, |x| x == "ZZZ")
+use crate::{
+    math::{crt, lcm_all},
+    Error, ParseError,
+};
+
+pub type Input<'a> = (Vec<Dir>, Network<'a>);
+
+pub fn p1((directions, map): &Input) -> Result<usize, Error> {
+    count_steps(directions, "AAA", map, |x| x == "ZZZ").map(|(steps, _)| steps)
+}
+
+pub fn p2((directions, map): &Input) -> Result<u64, Error> {
+    let starts: Vec<_> = map.keys().filter(|k| k.ends_with('A')).collect();
+    if starts.is_empty() {
+        return Err(Error::unsolvable("there are no starting nodes"));
+    }
+
+    let periods: Option<Vec<_>> = starts
+        .iter()
+        .map(|start| simple_period(directions, start, map).map(|n| n as u64))
+        .collect();
+    if let Some(periods) = periods {
+        return lcm_all(&periods).ok_or_else(|| Error::unsolvable("the step count overflows"));
+    }
+
+    let cycles = starts
+        .iter()
+        .map(|start| Cycle::find(directions, start, map))
+        .collect::<Result<Vec<_>, _>>()?;
+    first_common(&cycles)
+        .ok_or_else(|| Error::unsolvable("the ghosts never all reach an end at once"))
 }
 
-pub fn p2(input: &str) -> usize {
-    let (directions, map) = parse_input(input);
+#[derive(Clone, Copy)]
+pub enum Dir {
+    L,
+    R,
+}
+
+pub type Network<'a> = BTreeMap<&'a str, (&'a str, &'a str)>;
 
-    map.keys()
-        .filter(|k| k.ends_with('A'))
-        .map(|start| count_steps(directions, start, &map, |x| x.ends_with('Z')))
-        .reduce(lcm)
-        .unwrap()
+fn is_ghost_end(node: &str) -> bool {
+    node.ends_with('Z')
 }
 
-fn count_steps(
-    directions: &str,
-    start: &str,
-    map: &BTreeMap<&str, (&str, &str)>,
+fn step<'a>(map: &Network<'a>, cur: &str, dir: Dir) -> Result<&'a str, Error> {
+    let &(left, right) = map
+        .get(cur)
+        .ok_or_else(|| Error::unsolvable(format!("there is no node {cur}")))?;
+    Ok(match dir {
+        Dir::L => left,
+        Dir::R => right,
+    })
+}
+
+/// The number of steps to the first end, and that end.
+fn count_steps<'a>(
+    directions: &[Dir],
+    start: &'a str,
+    map: &Network<'a>,
     is_end: fn(&str) -> bool,
-) -> usize {
-    let mut step_count = 0;
+) -> Result<(usize, &'a str), Error> {
+    // past this many steps some (node, direction) state has repeated without
+    // reaching an end, so none will ever be reached
+    let max_steps = directions.len() * map.len();
+
     let mut cur = start;
-    for dir in directions.chars().cycle() {
-        cur = match dir {
-            'L' => map[cur].0,
-            'R' => map[cur].1,
-            dir => unreachable!("Invalid dir {dir}"),
-        };
-        step_count += 1;
+    for (step_count, &dir) in directions.iter().cycle().take(max_steps).enumerate() {
+        cur = step(map, cur, dir)?;
         if is_end(cur) {
-            break;
+            return Ok((step_count + 1, cur));
         }
     }
 
-    step_count
+    Err(Error::unsolvable(format!("{start} never reaches an end")))
 }
 
-fn parse_input(input: &str) -> (&str, BTreeMap<&str, (&str, &str)>) {
-    let (directions, rest) = input.trim().split_once("\n\n").unwrap();
+/// The period of a ghost that reaches an end every `period` steps from the
+/// start and at no other time, which the puzzle inputs happen to guarantee.
+fn simple_period(directions: &[Dir], start: &str, map: &Network) -> Option<usize> {
+    let (steps, end) = count_steps(directions, start, map, is_ghost_end).ok()?;
+    // back at the start of the directions on the same end, the ghost can only
+    // repeat the same loop
+    let repeats = steps % directions.len() == 0
+        && count_steps(directions, end, map, is_ghost_end).ok()? == (steps, end);
+    repeats.then_some(steps)
+}
 
-    let map: BTreeMap<_, _> = rest
-        .lines()
-        .map(|line| {
-            let (k, branches) = line.split_once(" = ").unwrap();
-            let v = branches.trim_matches(['(', ')']).split_once(", ").unwrap();
-            (k, v)
-        })
-        .collect();
-    (directions, map)
+/// When a ghost stands on an end: at each step of `pre`, and from `start` on
+/// at each step of `offsets` plus any multiple of `period`.
+#[derive(Debug, PartialEq)]
+struct Cycle {
+    pre: Vec<usize>,
+    start: usize,
+    offsets: Vec<usize>,
+    period: usize,
 }
 
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
+impl Cycle {
+    /// Walks from `start` until a (node, direction index) state repeats.
+    fn find(directions: &[Dir], start: &str, map: &Network) -> Result<Self, Error> {
+        let mut seen = HashMap::new();
+        let mut path = vec![];
+        let mut cur = start;
+
+        let (first, period) = loop {
+            let t = path.len();
+            let i = t % directions.len();
+            if let Some(&first) = seen.get(&(cur, i)) {
+                break (first, t - first);
+            }
+            seen.insert((cur, i), t);
+            path.push(cur);
+            cur = step(map, cur, directions[i])?;
+        };
+
+        // step 0 doesn't count, so a cycle from the start is read from step 1
+        let start = first.max(1);
+        let node_at = |t: usize| path[if t < path.len() { t } else { t - period }];
+
+        Ok(Self {
+            pre: (1..start).filter(|&t| is_ghost_end(path[t])).collect(),
+            start,
+            offsets: (start..start + period)
+                .filter(|&t| is_ghost_end(node_at(t)))
+                .collect(),
+            period,
+        })
+    }
+
+    fn contains(&self, t: usize) -> bool {
+        self.pre.contains(&t)
+            || t >= self.start
+                && self
+                    .offsets
+                    .contains(&(self.start + (t - self.start) % self.period))
     }
 }
 
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
+/// The first step at which every ghost stands on an end.
+fn first_common(cycles: &[Cycle]) -> Option<u64> {
+    // before the last ghost settles, only its pre-cycle ends can be common
+    let early = cycles
+        .iter()
+        .flat_map(|c| &c.pre)
+        .filter(|&&t| cycles.iter().all(|c| c.contains(t)))
+        .min()
+        .map(|&t| t as u64);
+
+    let settled = cycles.iter().map(|c| c.start as u64).max()?;
+    let congruences = cycles.iter().fold(vec![(0, 1)], |acc, c| {
+        acc.iter()
+            .flat_map(|&(x, m)| {
+                c.offsets.iter().filter_map(move |&offset| {
+                    crt(&[(x as i64, m), (offset as i64, c.period as u64)])
+                })
+            })
+            .collect()
+    });
+    let late = congruences
+        .into_iter()
+        .filter_map(|(x, m)| match settled.checked_sub(x) {
+            Some(behind) if behind > 0 => behind.div_ceil(m).checked_mul(m)?.checked_add(x),
+            _ => Some(x),
+        })
+        .min();
+
+    early.into_iter().chain(late).min()
+}
+
+fn parse_input(input: &str) -> Result<Input<'_>, Error> {
+    let mut lines = input.trim().lines().enumerate();
+
+    let (_, first) = lines.next().unwrap_or((0, ""));
+    let directions = first
+        .char_indices()
+        .map(|(i, c)| match c {
+            'L' => Ok(Dir::L),
+            'R' => Ok(Dir::R),
+            c => Err(ParseError::at(
+                0,
+                first,
+                &first[i..],
+                format!("invalid direction {c:?}"),
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if directions.is_empty() {
+        return Err(ParseError::new(1, 1, first, "expected directions").into());
+    }
+
+    let map = lines
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            let (k, branches) = line
+                .split_once(" = ")
+                .ok_or_else(|| ParseError::at(i, line, "", "expected ` = `"))?;
+            let v = branches
+                .strip_prefix('(')
+                .and_then(|b| b.strip_suffix(')'))
+                .and_then(|b| b.split_once(", "))
+                .ok_or_else(|| ParseError::at(i, line, branches, "expected `(LEFT, RIGHT)`"))?;
+            Ok((k, v))
+        })
+        .collect::<Result<_, ParseError>>()?;
+
+    Ok((directions, map))
 }
 
 #[cfg(test)]
@@ -87,8 +232,16 @@ ZZZ = (ZZZ, ZZZ)";
 
     #[test]
     fn test_p1() {
-        assert_eq!(p1(INPUT), 2);
-        assert_eq!(p1(INPUT_ALT), 6);
+        assert_eq!(p1(&parse_input(INPUT).unwrap()).unwrap(), 2);
+        assert_eq!(p1(&parse_input(INPUT_ALT).unwrap()).unwrap(), 6);
+        assert!(matches!(
+            p1(&parse_input("L\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").unwrap()),
+            Err(Error::Unsolvable(_))
+        ));
+        assert!(matches!(
+            parse_input("LX\n\nAAA = (ZZZ, ZZZ)"),
+            Err(Error::Parse(_))
+        ));
     }
 
     #[test]
@@ -104,8 +257,51 @@ ZZZ = (ZZZ, ZZZ)";
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
 
-        assert_eq!(p2(input), 6);
+        assert_eq!(p2(&parse_input(input).unwrap()).unwrap(), 6);
+    }
+
+    #[test]
+    fn test_p2_cycles() {
+        // 11A ends every 2 steps, 22A at step 1 and then every 3 steps
+        let input = "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)";
+        let (directions, map) = parse_input(input).unwrap();
+        assert_eq!(simple_period(&directions, "11A", &map), Some(2));
+        assert_eq!(simple_period(&directions, "22A", &map), None);
+        assert_eq!(
+            Cycle::find(&directions, "22A", &map).unwrap(),
+            Cycle {
+                pre: vec![],
+                start: 1,
+                offsets: vec![1],
+                period: 3
+            }
+        );
+        assert_eq!(p2(&(directions, map)).unwrap(), 4);
+
+        // 11A only ends at step 1, before settling
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22Z, 22Z)";
+        assert_eq!(p2(&parse_input(input).unwrap()).unwrap(), 1);
+
+        let input = input.replace("22A = (22Z, 22Z)", "22A = (22B, 22B)\n22B = (22Z, 22Z)");
+        assert!(matches!(
+            p2(&parse_input(&input).unwrap()),
+            Err(Error::Unsolvable(_))
+        ));
     }
 }
 
-aoc::solve!(2023, 8, p1, p2);
+crate::solve!(2023, 8, "Haunted Wasteland", parse_input, p1, p2);
@@ -6,3 +6,14 @@ pub mod day05;
 pub mod day06;
 pub mod day07;
 pub mod day08;
+
+pub const DAYS: &[crate::Day] = &[
+    day01::DAY,
+    day02::DAY,
+    day03::DAY,
+    day04::DAY,
+    day05::DAY,
+    day06::DAY,
+    day07::DAY,
+    day08::DAY,
+];